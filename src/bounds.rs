@@ -0,0 +1,114 @@
+//! Keeps stray entities from piling up: anything moving that leaves the play area
+//! and anything that outlives its [`Lifetime`] is cleaned up.
+
+use bevy::prelude::*;
+
+use crate::{
+    components::*,
+    constants::{SCREEN_HALF_HEIGHT, SCREEN_HALF_WIDTH},
+    entities::projectile::{Projectile, Trajectory},
+    AppSystems, GameplaySystems, PausableSystems,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PlayBounds>()
+        .init_resource::<CleanupStats>()
+        .add_systems(
+            Update,
+            expire_lifetimes
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems)
+                .in_set(GameplaySystems),
+        )
+        .add_systems(
+            Update,
+            clean_up_out_of_bounds
+                .in_set(AppSystems::Update)
+                .in_set(PausableSystems)
+                .in_set(GameplaySystems),
+        );
+}
+
+/// How far past the screen edges things can wander before they're cleaned up.
+#[derive(Resource)]
+pub struct PlayBounds {
+    pub margin: f32,
+}
+
+impl Default for PlayBounds {
+    fn default() -> Self {
+        Self { margin: 128.0 }
+    }
+}
+
+impl PlayBounds {
+    pub fn half_size(&self) -> Vec2 {
+        Vec2::new(SCREEN_HALF_WIDTH, SCREEN_HALF_HEIGHT) + self.margin
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        let half_size = self.half_size();
+        position.x.abs() <= half_size.x && position.y.abs() <= half_size.y
+    }
+}
+
+/// Running totals of what's been cleaned up, for the dev tools.
+#[derive(Resource, Default, Debug)]
+pub struct CleanupStats {
+    /// despawned for leaving the play area
+    pub out_of_bounds: u32,
+    /// despawned when their lifetime ran out
+    pub expired: u32,
+    /// enemies brought back in from the other side
+    pub recycled: u32,
+}
+
+fn expire_lifetimes(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Lifetime)>,
+    mut stats: ResMut<CleanupStats>,
+    time: Res<Time>,
+) {
+    for (entity, mut lifetime) in &mut query {
+        lifetime.0.tick(time.delta());
+        if lifetime.0.just_finished() {
+            commands.entity(entity).try_despawn();
+            stats.expired += 1;
+        }
+    }
+}
+
+/// despawns whatever flies off, except enemies: the wave is waiting on them,
+/// so they come back in on the other side
+fn clean_up_out_of_bounds(
+    mut commands: Commands,
+    mut query: Query<
+        (Entity, &mut Transform, Option<&Projectile>, Has<Enemy>),
+        (Or<(With<Moving>, With<Projectile>)>, Without<ScreenWrap>),
+    >,
+    bounds: Res<PlayBounds>,
+    mut stats: ResMut<CleanupStats>,
+) {
+    let half_size = bounds.half_size();
+    for (entity, mut transform, maybe_projectile, is_enemy) in &mut query {
+        let position = transform.translation.xy();
+        if bounds.contains(position) {
+            continue;
+        }
+        // arcs always come down where they were aimed
+        if maybe_projectile
+            .is_some_and(|projectile| matches!(projectile.trajectory, Trajectory::Arc { .. }))
+        {
+            continue;
+        }
+
+        if is_enemy {
+            let wrapped = (position + half_size).rem_euclid(half_size * 2.0) - half_size;
+            transform.translation = wrapped.extend(transform.translation.z);
+            stats.recycled += 1;
+        } else {
+            commands.entity(entity).try_despawn();
+            stats.out_of_bounds += 1;
+        }
+    }
+}
@@ -1,12 +1,29 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 
-use crate::constants::SCREEN_WIDTH;
+use crate::{
+    constants::SCREEN_WIDTH, damage::DamageKind, entities::archetype::EnemyArchetype,
+    spawners::enemies::SpawnPattern,
+};
+
+/// slowdown per stack of slow
+const SLOW_PER_STACK: f32 = 0.25;
+/// how finely spline paths are cut up into waypoints
+const SPLINE_SAMPLES_PER_POINT: usize = 8;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<AnimationConfig>()
+        .register_type::<Armor>()
         .register_type::<Bomb>()
+        .register_type::<BombAmmo>()
+        .register_type::<BombKind>()
+        .register_type::<Chain>()
+        .register_type::<DetonationMode>()
         .register_type::<Explosion>()
         .register_type::<MovementConfig>()
+        .register_type::<Path>()
+        .register_type::<Resistances>()
+        .register_type::<SelectedBomb>()
+        .register_type::<StatusEffects>()
         .register_type::<Spawner>()
         .register_type::<TargetDistance>()
         .register_type::<Wave>()
@@ -45,7 +62,23 @@ impl AnimationConfig {
 }
 
 #[derive(Component)]
-pub struct AssetIdx(pub usize);
+pub struct AmmoLabel;
+
+/// which archetype an enemy (or its spawner) was built from
+#[derive(Component)]
+pub struct Archetype(pub Handle<EnemyArchetype>);
+
+/// a remote bomb that has landed and is waiting to be detonated
+#[derive(Component)]
+pub struct Armed {
+    /// elapsed seconds when it landed, so the oldest can go first
+    pub since: f32,
+}
+
+/// flat reduction to every hit taken, though a hit always does at least 1
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct Armor(pub i32);
 
 #[derive(Component)]
 pub struct Attacking;
@@ -61,26 +94,243 @@ pub struct Background;
 #[derive(Component)]
 pub struct Blastable;
 
+/// the chain reaction that last blasted this entity
+#[derive(Component)]
+pub struct BlastedBy {
+    pub root: Entity,
+    pub depth: u32,
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Bomb {
     pub timer: Timer,
 }
 
+/// how many bombs the player can throw and how fast they come back
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct BombAmmo {
+    pub capacity: u32,
+    pub current: u32,
+    /// time to regain a single bomb
+    pub regen: Timer,
+}
+
+impl BombAmmo {
+    pub fn new(capacity: u32, regen_secs: f32) -> Self {
+        Self {
+            capacity,
+            current: capacity,
+            regen: Timer::from_seconds(regen_secs, TimerMode::Repeating),
+        }
+    }
+
+    /// takes a bomb if there is one, returning whether it did
+    pub fn try_take(&mut self) -> bool {
+        if self.current > 0 {
+            self.current -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.current >= self.capacity
+    }
+
+    pub fn refill(&mut self) {
+        self.current = self.capacity;
+        self.regen.reset();
+    }
+}
+
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum BombKind {
+    #[default]
+    Standard,
+    /// splits into bomblets when it goes off
+    Cluster,
+    /// one of the small bombs a cluster splits into, not selectable
+    Bomblet,
+    /// sticks to the first enemy it lands near
+    Sticky,
+    /// stuns without doing damage
+    Freeze,
+    /// leaves a burning patch on the ground
+    Napalm,
+}
+
+/// per-kind tuning for a bomb
+pub struct BombStats {
+    /// seconds before it goes off on its own
+    pub fuse: f32,
+    /// units per second while tossed
+    pub toss_speed: f32,
+    pub size: f32,
+    pub tint: Color,
+    /// blast damage before chain scaling
+    pub damage: i32,
+    pub damage_kind: DamageKind,
+    /// effects left on enemies caught in the blast, with their duration
+    pub statuses: &'static [(StatusKind, f32)],
+    /// multiplier on blast radius
+    pub range_scale: f32,
+    /// go off as soon as the toss lands instead of waiting for the fuse
+    pub detonate_on_land: bool,
+    /// go off when an enemy gets this close
+    pub proximity_radius: Option<f32>,
+}
+
+impl BombKind {
+    /// the kinds the player can cycle through, in order
+    pub const SELECTABLE: [BombKind; 5] = [
+        BombKind::Standard,
+        BombKind::Cluster,
+        BombKind::Sticky,
+        BombKind::Freeze,
+        BombKind::Napalm,
+    ];
+
+    pub fn stats(&self) -> BombStats {
+        match self {
+            BombKind::Standard => BombStats {
+                fuse: 2.75,
+                toss_speed: 200.0,
+                size: 64.0,
+                tint: Color::WHITE,
+                damage: 1,
+                damage_kind: DamageKind::Blast,
+                statuses: &[],
+                range_scale: 1.0,
+                detonate_on_land: true,
+                proximity_radius: None,
+            },
+            BombKind::Cluster => BombStats {
+                fuse: 2.75,
+                toss_speed: 175.0,
+                size: 72.0,
+                tint: Color::srgb(1.0, 0.8, 0.4),
+                damage: 1,
+                damage_kind: DamageKind::Blast,
+                statuses: &[],
+                range_scale: 0.75,
+                detonate_on_land: true,
+                proximity_radius: None,
+            },
+            BombKind::Bomblet => BombStats {
+                fuse: 1.0,
+                toss_speed: 150.0,
+                size: 32.0,
+                tint: Color::srgb(1.0, 0.8, 0.4),
+                damage: 1,
+                damage_kind: DamageKind::Blast,
+                statuses: &[],
+                range_scale: 0.5,
+                detonate_on_land: true,
+                proximity_radius: Some(40.0),
+            },
+            BombKind::Sticky => BombStats {
+                fuse: 2.0,
+                toss_speed: 200.0,
+                size: 64.0,
+                tint: Color::srgb(0.6, 1.0, 0.5),
+                damage: 2,
+                damage_kind: DamageKind::Blast,
+                statuses: &[],
+                range_scale: 0.75,
+                detonate_on_land: false,
+                proximity_radius: None,
+            },
+            BombKind::Freeze => BombStats {
+                fuse: 2.75,
+                toss_speed: 200.0,
+                size: 64.0,
+                tint: Color::srgb(0.5, 0.8, 1.0),
+                damage: 0,
+                damage_kind: DamageKind::Cold,
+                statuses: &[(StatusKind::Freeze, 1.5), (StatusKind::Slow, 4.0)],
+                range_scale: 1.25,
+                detonate_on_land: true,
+                proximity_radius: Some(50.0),
+            },
+            BombKind::Napalm => BombStats {
+                fuse: 2.75,
+                toss_speed: 200.0,
+                size: 64.0,
+                tint: Color::srgb(1.0, 0.5, 0.3),
+                damage: 1,
+                damage_kind: DamageKind::Fire,
+                statuses: &[],
+                range_scale: 0.75,
+                detonate_on_land: true,
+                proximity_radius: None,
+            },
+        }
+    }
+
+    /// the next selectable kind, wrapping around
+    pub fn next(&self) -> BombKind {
+        let index = BombKind::SELECTABLE
+            .iter()
+            .position(|kind| kind == self)
+            .unwrap_or(0);
+        BombKind::SELECTABLE[(index + 1) % BombKind::SELECTABLE.len()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BombKind::Standard => "Standard",
+            BombKind::Cluster => "Cluster",
+            BombKind::Bomblet => "Bomblet",
+            BombKind::Sticky => "Sticky",
+            BombKind::Freeze => "Freeze",
+            BombKind::Napalm => "Napalm",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BombKindLabel;
+
 #[derive(Component, Clone)]
 pub struct Bomber;
 
+/// a big enemy that works through attack phases as it loses health
+#[derive(Component, Clone)]
+pub struct Boss;
+
+/// the bar along the bottom of the screen showing a boss's health
+#[derive(Component)]
+pub struct BossHealthBar(pub Entity);
+
+/// the filled part of a boss health bar
 #[derive(Component)]
-pub struct BombToss {
-    pub ease: EasingCurve<Vec2>,
-    pub bounce_up: EasingCurve<f32>,
-    pub bounce_down: EasingCurve<f32>,
-    pub height: f32,
+pub struct BossHealthFill(pub Entity);
+
+#[derive(Component, Default)]
+pub struct BossState {
+    /// index into the archetype's boss phases
+    pub phase: usize,
+    /// which attack of the phase comes next
+    pub next_attack: usize,
+    /// spawners this boss called in, shut down when it dies
+    pub minion_spawners: Vec<Entity>,
 }
 
 #[derive(Component)]
 pub struct Bullet;
 
+/// marks a bomb that was set off by another bomb's blast
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Chain {
+    pub root: Entity,
+    pub depth: u32,
+}
+
 #[derive(Component)]
 pub struct Character;
 
@@ -102,6 +352,40 @@ pub struct Dead {
     pub timer: Timer,
 }
 
+/// how the player's bombs go off once they land
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum DetonationMode {
+    /// bombs go off on their own
+    #[default]
+    Fuse,
+    /// bombs wait for the detonate action, which sets them all off
+    RemoteAll,
+    /// bombs wait for the detonate action, which sets off the oldest one
+    RemoteOldest,
+}
+
+impl DetonationMode {
+    pub fn next(&self) -> DetonationMode {
+        match self {
+            DetonationMode::Fuse => DetonationMode::RemoteAll,
+            DetonationMode::RemoteAll => DetonationMode::RemoteOldest,
+            DetonationMode::RemoteOldest => DetonationMode::Fuse,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DetonationMode::Fuse => "Fuse",
+            DetonationMode::RemoteAll => "Remote",
+            DetonationMode::RemoteOldest => "Remote (oldest)",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct DetonationLabel;
+
 #[derive(Component)]
 pub struct Done;
 
@@ -123,9 +407,26 @@ pub struct Explosion {
     pub timer: Timer,
 }
 
+/// an enemy steering with the rest of its flock
+#[derive(Component)]
+pub struct Flocking {
+    /// where the flock as a whole wants to go
+    pub heading: Vec2,
+}
+
 #[derive(Component, Clone)]
 pub struct Flying;
 
+/// an enemy keeping its slot in a formation behind a leader
+#[derive(Component)]
+pub struct FormationMember {
+    pub leader: Entity,
+    /// where its slot is relative to the leader
+    pub offset: Vec2,
+    /// its own speed, to go back to once it's on its own
+    pub speed: f32,
+}
+
 #[derive(Component, Clone)]
 pub struct Ground;
 
@@ -138,18 +439,30 @@ pub struct Health {
 #[derive(Component)]
 pub struct HealthLabel;
 
+/// a push from a blast, easing off over its timer
+#[derive(Component)]
+pub struct Knockback {
+    /// units per second at the start of the push
+    pub velocity: Vec2,
+    pub timer: Timer,
+}
+
 #[derive(Component)]
 pub struct Level;
 
+/// despawned once the timer runs out, wherever it is
 #[derive(Component)]
-pub struct LobShot {
-    pub height: f32,
-    pub timer: Timer,
-    pub ease_pos: EasingCurve<Vec2>,
-    pub ease_up: EasingCurve<f32>,
-    pub ease_down: EasingCurve<f32>,
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn from_seconds(secs: f32) -> Self {
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
 }
 
+#[derive(Component)]
+pub struct LobShot;
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct MovementConfig {
@@ -189,6 +502,15 @@ impl MovementConfig {
 #[derive(Component)]
 pub struct Moving;
 
+/// a burning patch left behind by a napalm bomb
+#[derive(Component)]
+pub struct Napalm {
+    pub tick: Timer,
+    pub radius: f32,
+    /// burn stacks given to enemies inside it each tick
+    pub damage: i32,
+}
+
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
 /// general "music" category (e.g. global background music, soundtrack).
 ///
@@ -196,6 +518,37 @@ pub struct Moving;
 #[derive(Component)]
 pub struct Music;
 
+/// waypoints an enemy walks through in order, before carrying straight on
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component)]
+pub struct Path {
+    pub points: Vec<Vec2>,
+    /// index of the waypoint being headed for
+    pub next: usize,
+}
+
+impl Path {
+    /// straight lines from point to point
+    pub fn polyline(points: Vec<Vec2>) -> Self {
+        Self { points, next: 0 }
+    }
+
+    /// a smooth curve through the points, sampled into a polyline
+    pub fn spline(points: Vec<Vec2>) -> Self {
+        let samples = points.len() * SPLINE_SAMPLES_PER_POINT;
+        match CubicCardinalSpline::new_catmull_rom(points.clone()).to_curve() {
+            Ok(curve) => Self::polyline(curve.iter_positions(samples).collect()),
+            // too few points to curve
+            Err(_) => Self::polyline(points),
+        }
+    }
+
+    /// the waypoint being headed for, if there are any left
+    pub fn target(&self) -> Option<Vec2> {
+        self.points.get(self.next).copied()
+    }
+}
+
 #[derive(Component)]
 pub struct PlaceBombObserver;
 
@@ -205,9 +558,42 @@ pub struct Player;
 #[derive(Component)]
 pub struct PlayerLabel;
 
+/// sets the bomb off when an enemy touches its collider, which is sized to the fuse's reach
+#[derive(Component)]
+pub struct ProximityFuse;
+
+/// flashes a UI element to show an action was refused
+#[derive(Component)]
+pub struct Refused {
+    pub timer: Timer,
+}
+
+/// a bomb that ignores its fuse and waits for the detonate action
+#[derive(Component)]
+pub struct RemoteFuse;
+
+/// fraction of each kind of damage shrugged off, 1.0 is immune and negative is a weakness
+#[derive(Component, Reflect, Default, Clone)]
+#[reflect(Component)]
+pub struct Resistances(pub HashMap<DamageKind, f32>);
+
+#[derive(Component)]
+pub struct ScoreLabel;
+
+/// the bomb kind the player will throw next
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct SelectedBomb(pub BombKind);
+
 #[derive(Component)]
 pub struct ScreenWrap;
 
+/// an enemy projecting a bubble that keeps outside blasts off its neighbours
+#[derive(Component)]
+pub struct ShieldCarrier {
+    pub radius: f32,
+}
+
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
 /// general "sound effect" category (e.g. footsteps, the sound of a magic spell, a door opening).
 ///
@@ -215,22 +601,142 @@ pub struct ScreenWrap;
 #[derive(Component)]
 pub struct SoundEffect;
 
+/// a piece of a splitter, counted off against [`PendingSplits`](crate::entities::enemy::PendingSplits)
+#[derive(Component)]
+pub struct SplitPiece;
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Spawner {
     pub all_spawned: bool,
+    /// wait before the first spawn
+    pub delay: Timer,
+    /// where and how enemies come in
+    pub pattern: SpawnPattern,
     pub limit: usize,
     pub max_at_once: usize,
     pub spawned: usize,
     pub timer: Timer,
 }
 
-#[derive(Component, Reflect)]
+/// A lingering effect on an enemy.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+    /// can't move or attack
+    Stun,
+    /// takes fire damage every tick, more with each stack
+    Burn,
+    /// moves and attacks slower, more with each stack
+    Slow,
+    /// like a stun, but from the cold
+    Freeze,
+}
+
+impl StatusKind {
+    /// the order tints are picked in when several effects are active
+    pub const PRIORITY: [StatusKind; 4] = [
+        StatusKind::Freeze,
+        StatusKind::Stun,
+        StatusKind::Burn,
+        StatusKind::Slow,
+    ];
+
+    pub fn max_stacks(&self) -> u32 {
+        match self {
+            StatusKind::Burn | StatusKind::Slow => 3,
+            StatusKind::Stun | StatusKind::Freeze => 1,
+        }
+    }
+
+    /// seconds between ticks, for effects that do something periodically
+    pub fn tick_secs(&self) -> Option<f32> {
+        match self {
+            StatusKind::Burn => Some(0.5),
+            _ => None,
+        }
+    }
+
+    pub fn tint(&self) -> Color {
+        match self {
+            StatusKind::Stun => Color::srgb(1.0, 1.0, 0.5),
+            StatusKind::Burn => Color::srgb(1.0, 0.6, 0.3),
+            StatusKind::Slow => Color::srgb(0.75, 0.9, 1.0),
+            StatusKind::Freeze => Color::srgb(0.5, 0.8, 1.0),
+        }
+    }
+}
+
+#[derive(Reflect, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub stacks: u32,
+    pub duration: Timer,
+    pub tick: Option<Timer>,
+}
+
+/// every effect currently on an enemy
+#[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
-pub struct Speed(pub f32);
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    /// adds stacks of an effect, stacking up to its cap and keeping the longer duration
+    pub fn apply(&mut self, kind: StatusKind, secs: f32, stacks: u32) {
+        let stacks = stacks.min(kind.max_stacks());
+        if let Some(effect) = self.0.iter_mut().find(|effect| effect.kind == kind) {
+            effect.stacks = (effect.stacks + stacks).min(kind.max_stacks());
+            if effect.duration.remaining_secs() < secs {
+                effect.duration = Timer::from_seconds(secs, TimerMode::Once);
+            }
+        } else {
+            self.0.push(StatusEffect {
+                kind,
+                stacks,
+                duration: Timer::from_seconds(secs, TimerMode::Once),
+                tick: kind
+                    .tick_secs()
+                    .map(|tick| Timer::from_seconds(tick, TimerMode::Repeating)),
+            });
+        }
+    }
+
+    pub fn stacks(&self, kind: StatusKind) -> u32 {
+        self.0
+            .iter()
+            .find(|effect| effect.kind == kind)
+            .map_or(0, |effect| effect.stacks)
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.stacks(kind) > 0
+    }
+
+    /// can't move or attack at all
+    pub fn is_immobilized(&self) -> bool {
+        self.has(StatusKind::Stun) || self.has(StatusKind::Freeze)
+    }
 
+    /// multiplier on movement speed and attack timers
+    pub fn speed_factor(&self) -> f32 {
+        if self.is_immobilized() {
+            0.0
+        } else {
+            (1.0 - self.stacks(StatusKind::Slow) as f32 * SLOW_PER_STACK).max(0.0)
+        }
+    }
+
+    /// colour of the most important effect, if any
+    pub fn tint(&self) -> Option<Color> {
+        StatusKind::PRIORITY
+            .iter()
+            .find(|kind| self.has(**kind))
+            .map(|kind| kind.tint())
+    }
+}
+
+/// a bomb stuck to an enemy, following it around
 #[derive(Component)]
-pub struct SubType<T>(pub T) where T: Component + Clone;
+pub struct StuckTo(pub Entity);
 
 #[derive(Component, Reflect)]
 pub struct TargetDistance(pub f32);
@@ -240,6 +746,10 @@ pub struct TargetPosition {
     pub position: Vec2,
 }
 
+/// enemies that blow up when blasted to death or when they crash into the player
+#[derive(Component, Clone)]
+pub struct VolatileOnDeath;
+
 #[derive(Component)]
 pub struct WasAttacking;
 
@@ -18,12 +18,39 @@ struct PlayerControlls;
 #[input_action(output = Vec2)]
 pub struct PlaceBomb;
 
+/// switch to the next kind of bomb
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct CycleBomb;
+
+/// set off armed remote bombs
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct Detonate;
+
+/// switch between fuse and remote detonation
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct CycleDetonation;
+
 fn create_controls(mut commands: Commands) {
     let mut actions = Actions::<PlayerControlls>::default();
     actions
         .bind::<PlaceBomb>()
         .to(MouseButton::Left)
         .with_conditions(Press::default());
+    actions
+        .bind::<CycleBomb>()
+        .to((KeyCode::KeyQ, KeyCode::Tab))
+        .with_conditions(Press::default());
+    actions
+        .bind::<Detonate>()
+        .to((KeyCode::Space, MouseButton::Right))
+        .with_conditions(Press::default());
+    actions
+        .bind::<CycleDetonation>()
+        .to(KeyCode::KeyE)
+        .with_conditions(Press::default());
     commands.spawn((
         Name::new("Player Controls"),
         actions,
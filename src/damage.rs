@@ -1,5 +1,25 @@
-use crate::{components::*, events::*, AppSystems, GameplaySystems, PausableSystems};
+use crate::{
+    components::*, events::*, physics::SpatialIndex, AppSystems, GameplaySystems, PausableSystems,
+};
 use bevy::prelude::*;
+use serde::Deserialize;
+
+/// fraction of the blast radius that takes full damage
+const BLAST_CORE: f32 = 0.25;
+/// fraction of the damage left at the very edge of the blast
+const BLAST_EDGE_DAMAGE: f32 = 0.25;
+/// how long a knockback push lasts
+const KNOCKBACK_SECS: f32 = 0.3;
+
+/// What dealt the damage, so armor and resistances can tell hits apart.
+#[derive(Reflect, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Blast,
+    Fire,
+    Cold,
+    /// bullets and things crashing into you
+    Impact,
+}
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
@@ -12,24 +32,59 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 fn apply_blast_damage(
+    mut commands: Commands,
     mut blast_reader: EventReader<BlastEvent>,
     mut damage_writer: EventWriter<DamageEvent>,
-    enemy_query: Query<(Entity, &GlobalTransform), (With<Damageable>, Without<Dead>, With<Enemy>)>,
+    mut enemy_query: Query<
+        (&GlobalTransform, Option<&mut StatusEffects>),
+        (With<Damageable>, Without<Dead>, With<Enemy>),
+    >,
+    shield_query: Query<(Entity, &GlobalTransform, &ShieldCarrier), Without<Dead>>,
+    index: Res<SpatialIndex>,
 ) -> Result {
     if !blast_reader.is_empty() {
         for blast_event in blast_reader.read() {
             // let blast_trans = blast_query.get(blast_event.source)?;
-            for (enemy, enemy_trans) in &enemy_query {
-                if enemy_trans
-                    .translation()
-                    .xy()
-                    .distance(blast_event.location)
-                    <= 100.0
+            for enemy in index.nearby(blast_event.location, blast_event.damage_range) {
+                let Ok((enemy_trans, maybe_effects)) = enemy_query.get_mut(enemy) else {
+                    continue;
+                };
+                let position = enemy_trans.translation().xy();
+                let offset = position - blast_event.location;
+                let distance = offset.length();
+                if distance <= blast_event.damage_range
+                    && !is_shielded(enemy, position, blast_event.location, &shield_query)
                 {
-                    // blasted
+                    let falloff = blast_falloff(distance, blast_event.damage_range);
+
+                    // blasted, always at least 1 if the blast does damage
                     damage_writer.write(DamageEvent {
                         target: enemy,
-                        amount: 1,
+                        amount: (blast_event.damage as f32 * falloff).ceil() as i32,
+                        kind: blast_event.damage_kind,
+                        from_status: false,
+                    });
+
+                    if let Some(mut effects) = maybe_effects {
+                        for (kind, secs) in blast_event.statuses {
+                            effects.apply(*kind, *secs, 1);
+                        }
+                    }
+
+                    // shove it away from the centre
+                    if blast_event.knockback > 0.0 {
+                        commands.entity(enemy).insert(Knockback {
+                            velocity: offset.normalize_or(Vec2::X)
+                                * blast_event.knockback
+                                * falloff,
+                            timer: Timer::from_seconds(KNOCKBACK_SECS, TimerMode::Once),
+                        });
+                    }
+
+                    // remember which chain hit it for scoring
+                    commands.entity(enemy).insert(BlastedBy {
+                        root: blast_event.root,
+                        depth: blast_event.depth,
                     });
                 }
             }
@@ -38,3 +93,30 @@ fn apply_blast_damage(
 
     Ok(())
 }
+
+/// whether some other enemy's bubble covers `position` but not the blast
+fn is_shielded(
+    enemy: Entity,
+    position: Vec2,
+    blast_location: Vec2,
+    shield_query: &Query<(Entity, &GlobalTransform, &ShieldCarrier), Without<Dead>>,
+) -> bool {
+    shield_query.iter().any(|(carrier, carrier_trans, shield)| {
+        let centre = carrier_trans.translation().xy();
+        // carriers aren't covered by their own bubble, that's the weak spot
+        carrier != enemy
+            && position.distance(centre) <= shield.radius
+            && blast_location.distance(centre) > shield.radius
+    })
+}
+
+/// how much of a blast's strength reaches `distance` from its centre
+pub fn blast_falloff(distance: f32, range: f32) -> f32 {
+    let core = range * BLAST_CORE;
+    if distance <= core {
+        1.0
+    } else {
+        let edge_frac = ((distance - core) / (range - core)).clamp(0.0, 1.0);
+        1.0 - edge_frac * (1.0 - BLAST_EDGE_DAMAGE)
+    }
+}
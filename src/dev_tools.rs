@@ -7,7 +7,9 @@ use bevy::{
 
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 
-use crate::screens::Screen;
+use crate::{
+    bounds::CleanupStats, components::*, entities::projectile::Projectile, screens::Screen,
+};
 
 #[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
 pub(super) enum DebugState {
@@ -39,9 +41,19 @@ pub(super) fn plugin(app: &mut App) {
         .add_systems(
             Update,
             toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
+        )
+        // Show entity counts so leaks are easy to spot.
+        .add_systems(OnEnter(DebugState::ON), spawn_entity_counts)
+        .add_systems(OnExit(DebugState::ON), despawn_entity_counts)
+        .add_systems(
+            Update,
+            update_entity_counts.run_if(in_state(DebugState::ON)),
         );
 }
 
+#[derive(Component)]
+struct EntityCountLabel;
+
 const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
 
 fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
@@ -55,3 +67,45 @@ fn toggle_debug_off(mut debug: ResMut<NextState<DebugState>>) {
 fn toggle_debug_on(mut debug: ResMut<NextState<DebugState>>) {
     debug.set(DebugState::ON);
 }
+
+fn spawn_entity_counts(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Entity Counts"),
+        EntityCountLabel,
+        Text::default(),
+        TextFont::from_font_size(16.0),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        GlobalZIndex(10),
+    ));
+}
+
+fn despawn_entity_counts(mut commands: Commands, label: Single<Entity, With<EntityCountLabel>>) {
+    commands.entity(*label).despawn();
+}
+
+fn update_entity_counts(
+    mut label: Single<&mut Text, With<EntityCountLabel>>,
+    entities: Query<()>,
+    moving: Query<(), With<Moving>>,
+    projectiles: Query<(), With<Projectile>>,
+    enemies: Query<(), With<Enemy>>,
+    stats: Res<CleanupStats>,
+) {
+    label.0 = format!(
+        "entities: {}\nmoving: {}\nprojectiles: {}\nenemies: {}\n\
+         out of bounds: {}\nexpired: {}\nrecycled: {}",
+        entities.iter().len(),
+        moving.iter().len(),
+        projectiles.iter().len(),
+        enemies.iter().len(),
+        stats.out_of_bounds,
+        stats.expired,
+        stats.recycled,
+    );
+}
@@ -0,0 +1,254 @@
+//! Data-driven enemy definitions, loaded from `.enemy.ron` files listed in a roster.
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, LoadContext},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::Deserialize;
+
+use crate::damage::DamageKind;
+
+/// columns per row in the enemy sprite sheet
+const SHEET_COLUMNS: usize = 4;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<EnemyArchetype>()
+        .init_asset::<EnemyRoster>()
+        .init_asset_loader::<EnemyArchetypeLoader>()
+        .init_asset_loader::<EnemyRosterLoader>();
+}
+
+/// Everything that makes one kind of enemy different from another.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct EnemyArchetype {
+    /// the name waves refer to it by
+    pub name: String,
+    /// sprite size on screen
+    pub size: f32,
+    pub health: i32,
+    /// taken off every hit, see [`Armor`](crate::components::Armor)
+    #[serde(default)]
+    pub armor: i32,
+    /// fraction of each damage kind ignored, see [`Resistances`](crate::components::Resistances)
+    #[serde(default)]
+    pub resistances: HashMap<DamageKind, f32>,
+    /// min and max speed, as a fraction of the screen width per second
+    pub speed: (f32, f32),
+    /// distance from the player at which it starts attacking
+    pub engage_distance: f32,
+    /// random spread added to the engage distance
+    pub engage_jitter: f32,
+    /// blows up when blasted to death or when it crashes into the player
+    #[serde(default)]
+    pub volatile: bool,
+    pub walk: AnimationDef,
+    pub attack: AnimationDef,
+    pub behaviour: AttackBehaviour,
+    /// attack phases, only used by the `Boss` behaviour
+    #[serde(default)]
+    pub boss: Option<BossDef>,
+    /// smaller enemies it breaks into when killed
+    #[serde(default)]
+    pub split: Option<SplitDef>,
+    /// radius of the bubble it keeps outside blasts off its neighbours with
+    #[serde(default)]
+    pub shield_radius: Option<f32>,
+}
+
+/// A run of frames in the enemy sprite sheet.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AnimationDef {
+    pub row: usize,
+    pub frames: usize,
+    pub fps: usize,
+}
+
+impl AnimationDef {
+    /// atlas index of the first frame
+    pub fn index(&self) -> usize {
+        self.row * SHEET_COLUMNS
+    }
+}
+
+/// How an enemy goes after the player once it's close enough.
+#[derive(Component, Deserialize, Debug, Clone, Copy)]
+pub enum AttackBehaviour {
+    /// dives into the player and hits everything around where it lands
+    Kamikaze {
+        dive_speed: f32,
+        damage: i32,
+        /// how far the crash reaches, bombs close in go off and ones further out get shoved
+        impact_radius: f32,
+    },
+    /// fires a single bullet and then dies
+    Shooter { bullet_speed: f32 },
+    /// lobs shots over and over
+    Lobber {
+        interval: f32,
+        speed: f32,
+        height: f32,
+    },
+    /// works through the attacks of its current [`BossPhase`]
+    Boss,
+}
+
+/// What an enemy breaks into when it's killed.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SplitDef {
+    /// name of the archetype the pieces are
+    pub archetype: String,
+    pub count: u32,
+    /// how far the pieces fan out from the parent's heading, in degrees
+    pub spread: f32,
+}
+
+/// The phases a boss goes through as it loses health.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BossDef {
+    pub phases: Vec<BossPhase>,
+}
+
+impl BossDef {
+    /// index of the phase for a boss on `health_fraction` of its health
+    pub fn phase_for(&self, health_fraction: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| health_fraction <= phase.below)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BossPhase {
+    /// fraction of health at or below which this phase starts, the first phase should be 1.0
+    pub below: f32,
+    /// seconds between attacks
+    pub interval: f32,
+    /// attacks used in turn
+    pub attacks: Vec<BossAttack>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum BossAttack {
+    /// calls in reinforcements from the right edge
+    Minions {
+        archetype: String,
+        count: usize,
+        max_at_once: usize,
+    },
+    /// a fan of bullets aimed at the player
+    Volley {
+        bullets: u32,
+        /// total spread of the fan, in degrees
+        spread: f32,
+        speed: f32,
+    },
+    /// bullets that chase the player down
+    Missiles {
+        count: u32,
+        speed: f32,
+        /// how quickly they turn, in degrees per second
+        turn_rate: f32,
+    },
+    /// a fan of bullets that bounce off the screen edges
+    Ricochet {
+        bullets: u32,
+        /// total spread of the fan, in degrees
+        spread: f32,
+        speed: f32,
+        bounces: u32,
+    },
+    /// lob shots scattered around the player
+    Lobs {
+        count: u32,
+        /// how far from the player they can land
+        scatter: f32,
+        speed: f32,
+        height: f32,
+    },
+}
+
+/// The enemy archetypes available to waves.
+#[derive(Asset, TypePath, Debug)]
+pub struct EnemyRoster {
+    #[dependency]
+    pub archetypes: Vec<Handle<EnemyArchetype>>,
+}
+
+impl EnemyRoster {
+    /// looks up an archetype by its name
+    pub fn find<'a>(
+        &self,
+        name: &str,
+        archetypes: &'a Assets<EnemyArchetype>,
+    ) -> Option<(Handle<EnemyArchetype>, &'a EnemyArchetype)> {
+        self.archetypes.iter().find_map(|handle| {
+            archetypes
+                .get(handle)
+                .filter(|archetype| archetype.name == name)
+                .map(|archetype| (handle.clone(), archetype))
+        })
+    }
+}
+
+/// what a roster file looks like on disk
+#[derive(Deserialize)]
+struct RosterFile {
+    archetypes: Vec<String>,
+}
+
+#[derive(Default)]
+struct EnemyArchetypeLoader;
+
+impl AssetLoader for EnemyArchetypeLoader {
+    type Asset = EnemyArchetype;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemy.ron"]
+    }
+}
+
+#[derive(Default)]
+struct EnemyRosterLoader;
+
+impl AssetLoader for EnemyRosterLoader {
+    type Asset = EnemyRoster;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: RosterFile = ron::de::from_bytes(&bytes)?;
+        Ok(EnemyRoster {
+            archetypes: file
+                .archetypes
+                .into_iter()
+                .map(|path| load_context.load(path))
+                .collect(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["roster.ron"]
+    }
+}
@@ -3,18 +3,57 @@ use crate::{
     audio::{sound_effect, SfxAssets},
     components::*,
     constants::SCREEN_HALF_WIDTH,
-    events::BlastEvent,
+    controlls::{CycleBomb, CycleDetonation, Detonate},
+    damage::{blast_falloff, DamageKind},
+    entities::{
+        napalm::create_napalm_patch,
+        projectile::{projectile_bundle, Projectile, TossArc, Trajectory},
+    },
+    events::{BlastEvent, CollisionStarted, ProjectileLanded},
     menus::Menu,
+    physics::{Collider, CollisionLayer, FitToSprite, SpatialIndex},
     screens::Screen,
     vfx::{explosion::create_explosion_vfx, VfxAssets},
-    AppSystems, GameplaySystems, PausableSystems,
+    AppSystems, GameplaySystems, PausableSystems, Pause,
 };
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
+use bevy_enhanced_input::prelude::*;
 use bevy_prng::WyRand;
 use bevy_rand::global::GlobalEntropy;
 use rand::prelude::*;
 
+/// where bombs leave the player's hand, relative to the player
+pub const THROW_OFFSET: Vec2 = Vec2::new(24.0, 0.0);
+/// fraction of the sprite that counts as the bomb
+const BOMB_HITBOX: f32 = 0.5;
+/// how high a tossed bomb goes
+const TOSS_HEIGHT: f32 = 100.0;
+/// fraction of a toss spent rising before it falls
+const TOSS_APEX: f32 = 0.25;
+/// radius at which a root blast sets off other bombs
+const BLAST_CHAIN_RANGE: f32 = 50.0;
+/// radius at which a root blast damages enemies
+const BLAST_DAMAGE_RANGE: f32 = 100.0;
+/// how much each chain generation grows the blast radius
+const CHAIN_RANGE_GROWTH: f32 = 0.15;
+/// how hard a root blast pushes enemies, in units per second
+const BLAST_KNOCKBACK: f32 = 400.0;
+/// generations past this stop growing the blast
+const CHAIN_SCALING_CAP: u32 = 8;
+/// how many bomblets a cluster bomb splits into
+const CLUSTER_BOMBLETS: usize = 4;
+/// fraction of a crash's reach that sets bombs off, the rest just shoves them
+const IMPACT_CHAIN_FRACTION: f32 = 0.4;
+/// how hard a crash pushes things away, in units per second
+const IMPACT_KNOCKBACK: f32 = 300.0;
+/// how long a shove lasts
+const PUSH_SECS: f32 = 0.3;
+/// how far bomblets scatter from a cluster bomb
+const CLUSTER_SPREAD: f32 = 120.0;
+/// how close an enemy has to be for a sticky bomb to grab it
+const STICKY_RANGE: f32 = 60.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<BombAssets>(),
@@ -22,6 +61,9 @@ pub(super) fn plugin(app: &mut App) {
     app.add_event::<BlastEvent>();
     app.add_systems(OnEnter(Screen::Gameplay), add_click_to_spawn_observer);
     app.add_systems(OnEnter(Menu::None), add_click_to_spawn_observer);
+    app.add_observer(cycle_bomb_kind)
+        .add_observer(cycle_detonation_mode)
+        .add_observer(detonate_armed_bombs);
     app.add_systems(
         Update,
         (bomb_timer_countdown, countdown_to_exploding)
@@ -31,14 +73,18 @@ pub(super) fn plugin(app: &mut App) {
     );
     app.add_systems(
         Update,
-        chain_blast
+        (chain_blast, push_bombs, land_bombs)
             .in_set(AppSystems::Events)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
     );
     app.add_systems(
         Update,
-        (explode_exploding_bombs, move_towards_target)
+        (
+            explode_exploding_bombs,
+            follow_stuck_target,
+            (arm_proximity_fuses, check_proximity_fuses),
+        )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
@@ -56,32 +102,29 @@ pub struct BombAssets {
 
 pub fn create_bomb(
     assets: &BombAssets,
+    kind: BombKind,
+    start_pos: Vec2,
     position: Vec2,
-    timeout: f32,
-    speed: f32,
-    player_pos: Vec3,
 ) -> impl Bundle {
-    let start_pos = player_pos + Vec3::new(24.0, 0.0, 0.0);
-    let distance = start_pos.xy().distance(position);
-    let lerp_time = (distance / speed) / 2.0;
+    let stats = kind.stats();
+    let distance = start_pos.distance(position);
+    let lerp_time = (distance / stats.toss_speed) / 2.0;
     (
         Name::new("Bomb"),
         AnimationConfig::new(0, 8, 6),
         Animating,
         Bomb {
-            timer: Timer::from_seconds(timeout, TimerMode::Once),
-        },
-        BombToss {
-            height: 100.0,
-            ease: EasingCurve::new(start_pos.xy(), position, EaseFunction::Linear),
-            bounce_up: EasingCurve::new(0.0, 1.0, EaseFunction::CircularOut),
-            bounce_down: EasingCurve::new(1.0, 0.0, EaseFunction::BounceOut),
-        },
-        // for target position lerp
-        Countdown {
-            timer: Timer::from_seconds(lerp_time, TimerMode::Once),
+            timer: Timer::from_seconds(stats.fuse, TimerMode::Once),
         },
-        StateScoped(Screen::Gameplay),
+        kind,
+        projectile_bundle(
+            Projectile::new(Trajectory::arc(bomb_toss(start_pos, position), lerp_time)),
+            start_pos,
+        ),
+        (
+            Collider::circle(0.0, CollisionLayer::Bomb, &[]),
+            FitToSprite(BOMB_HITBOX),
+        ),
         Sprite {
             image: assets.ball.clone(),
             texture_atlas: Some(TextureAtlas {
@@ -89,14 +132,96 @@ pub fn create_bomb(
                 layout: assets.ball_layout.clone(),
                 // ..default()
             }),
-            custom_size: Some(Vec2::splat(64.0)),
+            custom_size: Some(Vec2::splat(stats.size)),
+            color: stats.tint,
             ..default()
         },
-        TargetPosition { position },
-        Transform::from_translation(start_pos),
     )
 }
 
+/// the arc a bomb follows from the player's hand to where it lands
+pub fn bomb_toss(start_pos: Vec2, position: Vec2) -> TossArc {
+    TossArc::new(
+        start_pos,
+        position,
+        TOSS_HEIGHT,
+        TOSS_APEX,
+        EaseFunction::BounceOut,
+    )
+}
+
+/// spawns a bomb along with any extra fuses its kind calls for
+pub fn spawn_bomb(
+    commands: &mut Commands,
+    assets: &BombAssets,
+    kind: BombKind,
+    start_pos: Vec2,
+    position: Vec2,
+) -> Entity {
+    let mut bomb = commands.spawn(create_bomb(assets, kind, start_pos, position));
+    if let Some(radius) = kind.stats().proximity_radius {
+        // the fuse reaches further than the bomb itself, and feels nothing until it's armed
+        bomb.insert(ProximityFuse)
+            .remove::<FitToSprite>()
+            .insert(Collider::circle(radius, CollisionLayer::Bomb, &[]));
+    }
+    bomb.id()
+}
+
+/// the blast a `kind` of bomb makes `depth` links down a chain
+/// deeper chains hit harder and wider
+pub fn create_blast(
+    kind: BombKind,
+    source: Entity,
+    location: Vec2,
+    root: Entity,
+    depth: u32,
+) -> BlastEvent {
+    let stats = kind.stats();
+    let scale = chain_range_scale(depth) * stats.range_scale;
+    BlastEvent {
+        source,
+        location,
+        range: BLAST_CHAIN_RANGE * scale,
+        damage_range: BLAST_DAMAGE_RANGE * scale,
+        damage: chain_damage(stats.damage, depth),
+        damage_kind: stats.damage_kind,
+        statuses: stats.statuses,
+        knockback: BLAST_KNOCKBACK * scale,
+        root,
+        depth,
+    }
+}
+
+/// the blast an enemy makes crashing down, anything it sets off is never scored as a chain
+pub fn create_impact(source: Entity, location: Vec2, damage: i32, radius: f32) -> BlastEvent {
+    BlastEvent {
+        source,
+        location,
+        range: radius * IMPACT_CHAIN_FRACTION,
+        damage_range: radius,
+        damage,
+        damage_kind: DamageKind::Impact,
+        statuses: &[],
+        knockback: IMPACT_KNOCKBACK,
+        root: source,
+        depth: 0,
+    }
+}
+
+/// blast radius multiplier for a bomb `depth` links down a chain
+pub fn chain_range_scale(depth: u32) -> f32 {
+    1.0 + depth.min(CHAIN_SCALING_CAP) as f32 * CHAIN_RANGE_GROWTH
+}
+
+/// blast damage for a bomb `depth` links down a chain, harmless bombs stay harmless
+pub fn chain_damage(base: i32, depth: u32) -> i32 {
+    if base <= 0 {
+        return 0;
+    }
+    base + depth.min(CHAIN_SCALING_CAP) as i32
+}
+
 fn add_click_to_spawn_observer(
     mut commands: Commands,
     prior: Option<Single<Entity, With<PlaceBombObserver>>>,
@@ -120,25 +245,98 @@ fn place_bomb_on_click(
     mut commands: Commands,
     assets: Res<BombAssets>,
     camera_query: Single<(&Camera, &GlobalTransform)>,
-    player_query: Single<&Transform, With<Player>>,
+    player_query: Single<(&Transform, &mut BombAmmo, &SelectedBomb, &DetonationMode), With<Player>>,
+    ammo_label: Single<Entity, With<AmmoLabel>>,
 ) {
+    // other buttons are bound to other actions
+    if trigger.button != PointerButton::Primary {
+        return;
+    }
+
     let (camera, camera_trans) = *camera_query;
     if let Ok(location) =
         camera.viewport_to_world_2d(camera_trans, trigger.pointer_location.position)
     {
-        commands.spawn(create_bomb(
+        let (player_trans, mut ammo, selected, mode) = player_query.into_inner();
+        if !ammo.try_take() {
+            // out of bombs, let the player know
+            commands.entity(*ammo_label).insert(Refused {
+                timer: Timer::from_seconds(0.5, TimerMode::Once),
+            });
+            return;
+        }
+
+        let bomb = spawn_bomb(
+            &mut commands,
             &assets,
+            selected.0,
+            player_trans.translation.xy() + THROW_OFFSET,
             location,
-            2.75,
-            200.0,
-            player_query.translation,
-        ));
+        );
+        if *mode != DetonationMode::Fuse {
+            commands.entity(bomb).insert(RemoteFuse);
+        }
+    }
+}
+
+fn cycle_bomb_kind(
+    _: Trigger<Fired<CycleBomb>>,
+    pause: Res<State<Pause>>,
+    mut selected: Single<&mut SelectedBomb, With<Player>>,
+) {
+    if pause.0 {
+        return;
+    }
+    selected.0 = selected.0.next();
+}
+
+fn cycle_detonation_mode(
+    _: Trigger<Fired<CycleDetonation>>,
+    pause: Res<State<Pause>>,
+    mut mode: Single<&mut DetonationMode, With<Player>>,
+) {
+    if pause.0 {
+        return;
+    }
+    **mode = mode.next();
+}
+
+fn detonate_armed_bombs(
+    _: Trigger<Fired<Detonate>>,
+    mut commands: Commands,
+    pause: Res<State<Pause>>,
+    mode: Single<&DetonationMode, With<Player>>,
+    armed_query: Query<(Entity, &Armed), (With<Bomb>, Without<Exploding>, Without<WillExplode>)>,
+) {
+    if pause.0 {
+        return;
+    }
+
+    if **mode == DetonationMode::RemoteOldest {
+        if let Some((oldest, _)) = armed_query
+            .iter()
+            .min_by(|a, b| a.1.since.total_cmp(&b.1.since))
+        {
+            mark_bomb_for_explode(&mut commands, oldest, 0.1);
+        }
+    } else {
+        // anything left armed goes off, even after switching back to fuses
+        for (bomb, _) in &armed_query {
+            mark_bomb_for_explode(&mut commands, bomb, 0.1);
+        }
     }
 }
 
 fn bomb_timer_countdown(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Bomb), (Without<Exploding>, Without<WillExplode>)>,
+    mut query: Query<
+        (Entity, &mut Bomb),
+        (
+            Without<Exploding>,
+            Without<WillExplode>,
+            Without<RemoteFuse>,
+        ),
+    >,
     time: Res<Time>,
 ) {
     for (entity, mut bomb) in &mut query {
@@ -159,20 +357,27 @@ fn mark_bomb_for_explode(commands: &mut Commands, entity: Entity, timeout: f32)
 fn explode_exploding_bombs(
     mut commands: Commands,
     assets: Res<VfxAssets>,
+    bomb_assets: Res<BombAssets>,
     sfx: Res<SfxAssets>,
     mut blast_writer: EventWriter<BlastEvent>,
-    mut exploding_bomb_query: Query<(Entity, &GlobalTransform), (With<Bomb>, With<Exploding>)>,
+    mut exploding_bomb_query: Query<
+        (Entity, &GlobalTransform, &BombKind, Option<&Chain>),
+        (With<Bomb>, With<Exploding>),
+    >,
     mut entropy: GlobalEntropy<WyRand>,
 ) {
     let count = exploding_bomb_query.iter().len();
-    for (entity, trans) in &mut exploding_bomb_query {
+    for (entity, trans, kind, maybe_chain) in &mut exploding_bomb_query {
         explode_bomb(
             &mut commands,
             &assets,
+            &bomb_assets,
             &sfx,
             &mut blast_writer,
             entity,
             trans,
+            *kind,
+            maybe_chain,
             &mut entropy,
             count,
         );
@@ -182,10 +387,13 @@ fn explode_exploding_bombs(
 fn explode_bomb(
     commands: &mut Commands,
     assets: &VfxAssets,
+    bomb_assets: &BombAssets,
     sfx: &SfxAssets,
     blast_writer: &mut EventWriter<BlastEvent>,
     entity: Entity,
     transform: &GlobalTransform,
+    kind: BombKind,
+    maybe_chain: Option<&Chain>,
     entropy: &mut GlobalEntropy<WyRand>,
     bomb_count: usize,
 ) {
@@ -204,38 +412,116 @@ fn explode_bomb(
         commands.spawn(sound_effect(random_step.clone(), 0.15));
     }
 
-    blast_writer.write(BlastEvent {
-        source: entity,
-        location: transform.translation().xy(),
-        range: 50.0,
-    });
+    // bombs without a chain are the root of their own chain
+    let (root, depth) = match maybe_chain {
+        Some(chain) => (chain.root, chain.depth),
+        None => (entity, 0),
+    };
+
+    let location = transform.translation().xy();
+    let blast = create_blast(kind, entity, location, root, depth);
+    let (damage_range, damage) = (blast.damage_range, blast.damage);
+    blast_writer.write(blast);
+
+    match kind {
+        BombKind::Cluster => {
+            // scatter bomblets around, they carry on the chain
+            for _ in 0..CLUSTER_BOMBLETS {
+                let angle = entropy.random_range(0.0..std::f32::consts::TAU);
+                let distance = entropy.random_range(CLUSTER_SPREAD / 2.0..CLUSTER_SPREAD);
+                let target = location + Vec2::from_angle(angle) * distance;
+                let bomblet =
+                    spawn_bomb(commands, bomb_assets, BombKind::Bomblet, location, target);
+                commands.entity(bomblet).insert(Chain {
+                    root,
+                    depth: depth + 1,
+                });
+            }
+        }
+        BombKind::Napalm => {
+            commands.spawn(create_napalm_patch(assets, location, damage_range, damage));
+        }
+        _ => {}
+    }
 }
 
 fn chain_blast(
     mut commands: Commands,
     mut blast_reader: EventReader<BlastEvent>,
-    mut bomb_query: Query<
-        (Entity, &GlobalTransform),
+    bomb_query: Query<
+        (&GlobalTransform, Option<&Chain>),
         (With<Bomb>, Without<Exploding>, Without<WillExplode>),
     >,
+    index: Res<SpatialIndex>,
 ) {
     if !blast_reader.is_empty() {
         for blast in blast_reader.read() {
-            for (bomb_ent, bomb_trans) in &mut bomb_query {
+            for bomb_ent in index.nearby(blast.location, blast.range) {
+                let Ok((bomb_trans, maybe_chain)) = bomb_query.get(bomb_ent) else {
+                    continue;
+                };
                 // skip if they're the same
                 if bomb_ent == blast.source {
                     continue;
                 }
+                // already part of this chain, e.g. bomblets leaving a cluster
+                else if maybe_chain.is_some_and(|chain| chain.root == blast.root) {
+                    continue;
+                }
                 // other bomb within distance, ASPLODE
                 else if blast.location.distance(bomb_trans.translation().truncate()) < blast.range
                 {
                     mark_bomb_for_explode(&mut commands, bomb_ent, 0.25);
+                    commands.entity(bomb_ent).insert(Chain {
+                        root: blast.root,
+                        depth: blast.depth + 1,
+                    });
                 }
             }
         }
     }
 }
 
+/// crashes shove resting bombs that are too far out to be set off
+fn push_bombs(
+    mut commands: Commands,
+    mut blast_reader: EventReader<BlastEvent>,
+    bomb_query: Query<
+        &GlobalTransform,
+        (
+            With<Bomb>,
+            Without<Exploding>,
+            Without<WillExplode>,
+            Without<Projectile>,
+            Without<StuckTo>,
+        ),
+    >,
+    index: Res<SpatialIndex>,
+) {
+    for blast in blast_reader.read() {
+        if blast.damage_kind != DamageKind::Impact {
+            continue;
+        }
+        for bomb_ent in index.nearby(blast.location, blast.damage_range) {
+            let Ok(bomb_trans) = bomb_query.get(bomb_ent) else {
+                continue;
+            };
+            let offset = bomb_trans.translation().xy() - blast.location;
+            let distance = offset.length();
+            // close ones are going off anyway, see `chain_blast`
+            if distance < blast.range || distance > blast.damage_range {
+                continue;
+            }
+            commands.entity(bomb_ent).insert(Knockback {
+                velocity: offset.normalize_or(Vec2::X)
+                    * blast.knockback
+                    * blast_falloff(distance, blast.damage_range),
+                timer: Timer::from_seconds(PUSH_SECS, TimerMode::Once),
+            });
+        }
+    }
+}
+
 fn countdown_to_exploding(
     mut commands: Commands,
     mut query: Query<(Entity, &mut WillExplode), (With<Bomb>, Without<Exploding>)>,
@@ -250,38 +536,98 @@ fn countdown_to_exploding(
     }
 }
 
-fn move_towards_target(
+fn land_bombs(
     mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &mut Transform,
-            &TargetPosition,
-            &mut Countdown,
-            &BombToss,
-        ),
+    mut landed_reader: EventReader<ProjectileLanded>,
+    bomb_query: Query<
+        (&BombKind, Has<RemoteFuse>),
         (With<Bomb>, Without<Exploding>, Without<WillExplode>),
     >,
+    enemy_query: Query<(Entity, &GlobalTransform), (With<Enemy>, With<Blastable>, Without<Dead>)>,
     time: Res<Time>,
 ) {
-    for (entity, mut trans, target_pos, mut countdown, bomb_toss) in &mut query {
-        countdown.timer.tick(time.delta());
-        if countdown.timer.just_finished() {
-            commands.entity(entity).remove::<TargetPosition>();
+    for landed in landed_reader.read() {
+        let Ok((kind, is_remote)) = bomb_query.get(landed.projectile) else {
+            continue;
+        };
+        let entity = landed.projectile;
+        if is_remote {
+            // sit and wait for the player
+            commands.entity(entity).insert(Armed {
+                since: time.elapsed_secs(),
+            });
+        }
+
+        if kind.stats().detonate_on_land && !is_remote {
             mark_bomb_for_explode(&mut commands, entity, 0.25);
-        } else {
-            let fraction = countdown.timer.fraction();
-            let mut new_pos = bomb_toss.ease.sample_clamped(fraction);
-
-            if fraction < 0.25 {
-                let up_frac = fraction / 0.25;
-                new_pos.y += bomb_toss.bounce_up.sample_clamped(up_frac) * bomb_toss.height;
-            } else {
-                let down_frac = (fraction - 0.25) / 0.75;
-                new_pos.y += bomb_toss.bounce_down.sample_clamped(down_frac) * bomb_toss.height;
+        } else if *kind == BombKind::Sticky {
+            // grab the closest enemy in reach and wait out the fuse
+            let closest = enemy_query
+                .iter()
+                .map(|(enemy, enemy_trans)| {
+                    (
+                        enemy,
+                        enemy_trans.translation().xy().distance(landed.position),
+                    )
+                })
+                .filter(|(_, distance)| *distance <= STICKY_RANGE)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((enemy, _)) = closest {
+                commands.entity(entity).insert(StuckTo(enemy));
             }
+        }
+    }
+}
+
+fn follow_stuck_target(
+    mut commands: Commands,
+    mut bomb_query: Query<(Entity, &mut Transform, &StuckTo), (With<Bomb>, Without<Exploding>)>,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+) {
+    for (entity, mut trans, stuck_to) in &mut bomb_query {
+        if let Ok(enemy_trans) = enemy_query.get(stuck_to.0) {
+            trans.translation = enemy_trans.translation().xy().extend(0.0);
+        } else {
+            // enemy is gone, stay where we are
+            commands.entity(entity).remove::<StuckTo>();
+        }
+    }
+}
+
+/// fuses start feeling for enemies once the toss is past the top,
+/// so anything already in reach then still counts as coming in
+fn arm_proximity_fuses(
+    mut fuse_query: Query<(&mut Collider, Option<&Projectile>), (With<Bomb>, With<ProximityFuse>)>,
+) {
+    for (mut collider, maybe_projectile) in &mut fuse_query {
+        // don't go off in the thrower's face while still rising
+        if collider.mask.is_empty()
+            && !maybe_projectile.is_some_and(|projectile| projectile.trajectory.is_rising())
+        {
+            collider.mask = &[CollisionLayer::Enemy];
+        }
+    }
+}
 
-            trans.translation = new_pos.extend(0.0);
+fn check_proximity_fuses(
+    mut commands: Commands,
+    mut collision_reader: EventReader<CollisionStarted>,
+    bomb_query: Query<
+        (),
+        (
+            With<Bomb>,
+            With<ProximityFuse>,
+            Without<Exploding>,
+            Without<WillExplode>,
+        ),
+    >,
+    enemy_query: Query<(), (With<Enemy>, With<Blastable>, Without<Dead>)>,
+) {
+    for collision in collision_reader.read() {
+        for (bomb, other) in [(collision.a, collision.b), (collision.b, collision.a)] {
+            if bomb_query.contains(bomb) && enemy_query.contains(other) {
+                mark_bomb_for_explode(&mut commands, bomb, 0.1);
+            }
         }
     }
 }
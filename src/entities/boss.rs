@@ -0,0 +1,251 @@
+use bevy::prelude::*;
+use bevy_prng::WyRand;
+use bevy_rand::global::GlobalEntropy;
+use rand::prelude::*;
+
+use crate::{
+    components::*,
+    entities::{
+        archetype::{BossAttack, EnemyArchetype, EnemyRoster},
+        bullet::{create_bullet, BulletAssets},
+        enemy::EnemyAssets,
+        lob_shot::{create_lob_shot, LobShotAssets},
+        projectile::Trajectory,
+    },
+    spawners::enemies::{create_enemy_spawner, SpawnPattern},
+    AppSystems, GameplaySystems, PausableSystems,
+};
+
+/// seconds between minions a boss calls in
+const MINION_RATE: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (update_boss_phase, shut_down_minion_spawners)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    )
+    .add_systems(
+        Update,
+        run_boss_attacks
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+fn update_boss_phase(
+    mut boss_query: Query<
+        (
+            &Health,
+            &Archetype,
+            &mut BossState,
+            Option<&mut AttackTimer>,
+        ),
+        (With<Boss>, Without<Dead>),
+    >,
+    archetypes: Res<Assets<EnemyArchetype>>,
+) {
+    for (health, archetype, mut state, maybe_timer) in &mut boss_query {
+        let Some(boss) = archetypes
+            .get(&archetype.0)
+            .and_then(|archetype| archetype.boss.as_ref())
+        else {
+            continue;
+        };
+
+        let fraction = health.current as f32 / health.max.max(1) as f32;
+        let phase = boss.phase_for(fraction);
+        if phase == state.phase {
+            continue;
+        }
+
+        // new phase, start its attacks from the top
+        info!("boss entering phase {}", phase + 1);
+        state.phase = phase;
+        state.next_attack = 0;
+        if let Some(mut attack_timer) = maybe_timer {
+            attack_timer.timer =
+                Timer::from_seconds(boss.phases[phase].interval, TimerMode::Repeating);
+        }
+    }
+}
+
+fn run_boss_attacks(
+    mut commands: Commands,
+    mut boss_query: Query<
+        (
+            &Transform,
+            &Archetype,
+            &StatusEffects,
+            &mut AttackTimer,
+            &mut BossState,
+        ),
+        (With<Boss>, With<Attacking>, Without<Dead>),
+    >,
+    player: Single<(Entity, &Transform), (With<Player>, Without<Boss>)>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    rosters: Res<Assets<EnemyRoster>>,
+    enemy_assets: Res<EnemyAssets>,
+    bullet_assets: Res<BulletAssets>,
+    lob_assets: Res<LobShotAssets>,
+    mut entropy: GlobalEntropy<WyRand>,
+    time: Res<Time>,
+) {
+    let (player_ent, player_trans) = *player;
+    let player_position = player_trans.translation.xy();
+    for (trans, archetype, effects, mut attack_timer, mut state) in &mut boss_query {
+        attack_timer
+            .timer
+            .tick(time.delta().mul_f32(effects.speed_factor()));
+        if !attack_timer.timer.just_finished() {
+            continue;
+        }
+
+        let Some(phase) = archetypes
+            .get(&archetype.0)
+            .and_then(|archetype| archetype.boss.as_ref())
+            .and_then(|boss| boss.phases.get(state.phase))
+        else {
+            continue;
+        };
+        if phase.attacks.is_empty() {
+            continue;
+        }
+
+        let attack = &phase.attacks[state.next_attack % phase.attacks.len()];
+        state.next_attack = (state.next_attack + 1) % phase.attacks.len();
+
+        let boss_position = trans.translation.xy();
+        match attack {
+            BossAttack::Minions {
+                archetype,
+                count,
+                max_at_once,
+            } => {
+                let Some((minion, _)) = rosters
+                    .get(&enemy_assets.roster)
+                    .and_then(|roster| roster.find(archetype, &archetypes))
+                else {
+                    warn!("no enemy archetype named {archetype}");
+                    continue;
+                };
+                let spawner = create_enemy_spawner(
+                    &mut commands,
+                    minion,
+                    *count,
+                    *max_at_once,
+                    MINION_RATE,
+                    0.0,
+                    SpawnPattern::default(),
+                );
+                state.minion_spawners.push(spawner);
+            }
+            BossAttack::Volley {
+                bullets,
+                spread,
+                speed,
+            } => {
+                let aim = (player_position - boss_position).normalize_or(Vec2::NEG_X);
+                for i in 0..*bullets {
+                    // fan the bullets evenly across the spread
+                    let offset = if *bullets > 1 {
+                        -spread / 2.0 + spread * i as f32 / (*bullets - 1) as f32
+                    } else {
+                        0.0
+                    };
+                    let direction = Vec2::from_angle(offset.to_radians()).rotate(aim);
+                    commands.spawn(create_bullet(
+                        &bullet_assets,
+                        boss_position,
+                        Trajectory::Linear {
+                            velocity: direction * *speed,
+                        },
+                    ));
+                }
+            }
+            BossAttack::Missiles {
+                count,
+                speed,
+                turn_rate,
+            } => {
+                for i in 0..*count {
+                    // launch them spread out, they find their way to the player
+                    let angle = std::f32::consts::TAU * i as f32 / *count as f32;
+                    commands.spawn(create_bullet(
+                        &bullet_assets,
+                        boss_position,
+                        Trajectory::Homing {
+                            target: player_ent,
+                            velocity: Vec2::from_angle(angle) * *speed,
+                            turn_rate: turn_rate.to_radians(),
+                        },
+                    ));
+                }
+            }
+            BossAttack::Ricochet {
+                bullets,
+                spread,
+                speed,
+                bounces,
+            } => {
+                for i in 0..*bullets {
+                    // fan them out wide, the walls send them back in
+                    let offset = if *bullets > 1 {
+                        -spread / 2.0 + spread * i as f32 / (*bullets - 1) as f32
+                    } else {
+                        0.0
+                    };
+                    let direction = Vec2::from_angle(offset.to_radians()).rotate(Vec2::NEG_X);
+                    commands.spawn(create_bullet(
+                        &bullet_assets,
+                        boss_position,
+                        Trajectory::Bouncing {
+                            velocity: direction * *speed,
+                            bounces: *bounces,
+                        },
+                    ));
+                }
+            }
+            BossAttack::Lobs {
+                count,
+                scatter,
+                speed,
+                height,
+            } => {
+                for _ in 0..*count {
+                    let target = player_position
+                        + Vec2::new(
+                            entropy.random_range(-scatter..=*scatter),
+                            entropy.random_range(-scatter..=*scatter),
+                        );
+                    commands.spawn(create_lob_shot(
+                        &lob_assets,
+                        *height,
+                        *speed,
+                        boss_position,
+                        target,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// a dead boss stops calling in help, so the wave can end once the stragglers are gone
+fn shut_down_minion_spawners(
+    mut commands: Commands,
+    boss_query: Query<&BossState, (With<Boss>, Added<Dead>)>,
+    mut spawner_query: Query<&mut Spawner>,
+) {
+    for state in &boss_query {
+        for spawner_ent in &state.minion_spawners {
+            if let Ok(mut spawner) = spawner_query.get_mut(*spawner_ent) {
+                spawner.all_spawned = true;
+                commands.entity(*spawner_ent).insert(Done);
+            }
+        }
+    }
+}
@@ -1,18 +1,28 @@
 use crate::{
-    assets::AssetsState, components::*, events::DamageEvent, AppSystems, GameplaySystems,
-    PausableSystems,
+    assets::AssetsState,
+    components::*,
+    damage::DamageKind,
+    entities::projectile::{projectile_bundle, Projectile, Trajectory},
+    events::BlastEvent,
+    physics::{Collider, CollisionLayer, FitToSprite, SpatialIndex},
+    AppSystems, GameplaySystems, PausableSystems,
 };
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+/// fraction of the sprite that can hit, the rest is glow
+const BULLET_HITBOX: f32 = 0.2;
+/// seconds before a bullet gives up, so homing ones can't circle forever
+const BULLET_LIFETIME: f32 = 8.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<BulletAssets>(),
     )
     .add_systems(
         Update,
-        (check_bullet_hit_player)
-            .in_set(AppSystems::Update)
+        blast_bullets
+            .in_set(AppSystems::Events)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
     );
@@ -27,45 +37,48 @@ pub struct BulletAssets {
 
 pub fn create_bullet(
     bullet_assets: &BulletAssets,
-    target_position: Vec2,
     start_position: Vec2,
-    speed: f32,
+    trajectory: Trajectory,
 ) -> impl Bundle {
-    let movement = (target_position - start_position).normalize();
-    let angle = Vec2::Y.angle_to(movement);
-    let transform = Transform::from_translation(start_position.extend(0.0))
-        .with_rotation(Quat::from_rotation_z(angle));
     (
         Name::new("Bullet"),
         Bullet,
-        MovementConfig::from_vec2(movement).with_speed(speed),
-        Moving,
+        projectile_bundle(
+            Projectile::new(trajectory).hitting(CollisionLayer::Player, 1, DamageKind::Impact),
+            start_position,
+        ),
+        (
+            Collider::circle(
+                0.0,
+                CollisionLayer::EnemyProjectile,
+                &[CollisionLayer::Player],
+            ),
+            FitToSprite(BULLET_HITBOX),
+        ),
+        Lifetime::from_seconds(BULLET_LIFETIME),
         Sprite {
             image: bullet_assets.bullet.clone(),
             custom_size: Some(Vec2::splat(15.0 * 3.0)),
             ..default()
         },
-        transform,
     )
 }
 
-pub fn check_bullet_hit_player(
+fn blast_bullets(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &GlobalTransform), (With<Bullet>, Without<Player>)>,
-    player_query: Single<(Entity, &GlobalTransform), (With<Player>, Without<Bullet>)>,
-    mut damage_writer: EventWriter<DamageEvent>,
+    mut blast_reader: EventReader<BlastEvent>,
+    bullet_query: Query<&GlobalTransform, With<Bullet>>,
+    index: Res<SpatialIndex>,
 ) {
-    let player_pos = player_query.1.translation().xy();
-    for (bullet, bullet_pos) in bullet_query.iter() {
-        if bullet_pos.translation().xy().distance(player_pos) <= 15.0 {
-            // hit player
-            commands.entity(bullet).despawn();
-
-            // inform player of damage
-            damage_writer.write(DamageEvent {
-                target: player_query.0,
-                amount: 1,
-            });
+    for blast in blast_reader.read() {
+        for bullet in index.nearby(blast.location, blast.damage_range) {
+            let Ok(bullet_trans) = bullet_query.get(bullet) else {
+                continue;
+            };
+            if bullet_trans.translation().xy().distance(blast.location) <= blast.damage_range {
+                // shot down
+                commands.entity(bullet).try_despawn();
+            }
         }
     }
 }
@@ -1,25 +1,48 @@
 use crate::{
     assets::AssetsState,
     components::*,
+    constants::SCREEN_WIDTH,
+    damage::DamageKind,
     entities::{
+        archetype::{AttackBehaviour, EnemyArchetype, EnemyRoster},
+        bombs::{create_blast, create_impact},
         bullet::{create_bullet, BulletAssets},
         lob_shot::{create_lob_shot, LobShotAssets},
+        projectile::Trajectory,
     },
-    events::{DamageEvent, EnemyDiedEvent},
+    events::{BlastEvent, DamageEvent, EnemyDiedEvent},
+    physics::{Collider, CollisionLayer, FitToSprite},
+    screens::Screen,
+    vfx::{explosion::create_explosion_vfx, VfxAssets},
     AppSystems, GameplaySystems, PausableSystems,
 };
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+/// fraction of the sprite that counts as the enemy's body, the sheet has a lot of padding
+const ENEMY_HITBOX: f32 = 0.6;
+/// how long an enemy is stunned when it takes a hit
+const DAMAGE_STUN_SECS: f32 = 0.5;
+/// how far from a dead splitter its pieces appear
+const SPLIT_OFFSET: f32 = 20.0;
+/// how much further and harder a volatile enemy's crash hits
+const VOLATILE_IMPACT_SCALE: f32 = 1.5;
+/// seconds between attacks for a boss with no phases set up
+const BOSS_FALLBACK_INTERVAL: f32 = 2.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<EnemyAssets>(),
     );
+    app.init_resource::<PendingSplits>()
+        .add_systems(OnEnter(Screen::Gameplay), reset_pending_splits);
     app.add_systems(
         Update,
         (
             handle_damaged,
             handle_dead,
+            detonate_volatile_dead,
+            (split_dead_enemies, count_split_pieces).chain(),
             switch_to_attack_player,
             move_to_player,
             fire_shot_at_player,
@@ -44,35 +67,56 @@ pub struct EnemyAssets {
     enemies: Handle<Image>,
     #[asset(texture_atlas_layout(tile_size_x = 30, tile_size_y = 30, columns = 4, rows = 48))]
     layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "enemies/enemies.roster.ron")]
+    pub roster: Handle<EnemyRoster>,
 }
 
+/// Pieces of killed splitters that haven't turned up yet, the wave isn't over until they have.
+#[derive(Resource, Default)]
+pub struct PendingSplits(pub usize);
+
 pub fn create_enemy(
-    enemy_type: impl Component,
+    archetype: &EnemyArchetype,
     enemy_assets: &EnemyAssets,
-    index: usize,
     position: Vec2,
     movement: Vec2,
     speed_percent: f32,
     target_distance: f32,
 ) -> impl Bundle {
+    let walk = archetype.walk;
     (
-        Name::new("Enemy"),
-        AnimationConfig::new(index, 4, 4),
+        Name::new(format!("Enemy ({})", archetype.name)),
+        AnimationConfig::new(walk.index(), walk.frames, walk.fps),
         Animating,
+        archetype.behaviour,
         Blastable,
         Damageable,
         Enemy,
-        enemy_type,
-        Health { current: 1, max: 1 },
+        Health {
+            current: archetype.health,
+            max: archetype.health,
+        },
         MovementConfig::from_vec2(movement).with_speed_as_screen_width_percent(speed_percent),
         Moving,
+        // how it takes hits, grouped to keep the bundle within the tuple limit
+        (
+            Armor(archetype.armor),
+            Resistances(archetype.resistances.clone()),
+            StatusEffects::default(),
+        ),
+        (
+            Collider::aabb(Vec2::ZERO, CollisionLayer::Enemy, &[]),
+            FitToSprite(ENEMY_HITBOX),
+        ),
         Sprite {
             image: enemy_assets.enemies.clone(),
             texture_atlas: Some(TextureAtlas {
                 layout: enemy_assets.layout.clone(),
-                index,
+                index: walk.index(),
             }),
-            custom_size: Some(Vec2::splat(30.0 * 3.0)),
+            custom_size: Some(Vec2::splat(archetype.size)),
+            // sprites face left, turn them around when coming in from the left
+            flip_x: movement.x > 0.0,
             ..default()
         },
         TargetDistance(target_distance),
@@ -81,73 +125,60 @@ pub fn create_enemy(
     )
 }
 
+/// spawns an enemy from its archetype, along with the markers its behaviour needs
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    handle: Handle<EnemyArchetype>,
+    archetype: &EnemyArchetype,
+    enemy_assets: &EnemyAssets,
+    position: Vec2,
+    movement: Vec2,
+    speed_percent: f32,
+    target_distance: f32,
+) -> Entity {
+    let mut enemy = commands.spawn((
+        create_enemy(
+            archetype,
+            enemy_assets,
+            position,
+            movement,
+            speed_percent,
+            target_distance,
+        ),
+        Archetype(handle),
+    ));
+    match archetype.behaviour {
+        AttackBehaviour::Kamikaze { .. } => enemy.insert(Flying),
+        AttackBehaviour::Shooter { .. } => enemy.insert(Ground),
+        AttackBehaviour::Lobber { .. } => enemy.insert(Bomber),
+        AttackBehaviour::Boss => enemy.insert((Boss, BossState::default())),
+    };
+    if archetype.volatile {
+        enemy.insert(VolatileOnDeath);
+    }
+    if let Some(radius) = archetype.shield_radius {
+        enemy.insert(ShieldCarrier { radius });
+    }
+    enemy.id()
+}
+
 fn handle_damaged(
     mut commands: Commands,
     mut damaged_query: Query<
-        (
-            Entity,
-            &mut Sprite,
-            &mut Damaged,
-            Option<&Moving>,
-            Option<&Attacking>,
-            Option<&WasMoving>,
-            Option<&WasAttacking>,
-        ),
+        (Entity, &mut Damaged, &mut StatusEffects, Has<Boss>),
         (With<Enemy>, Without<Dead>),
     >,
     time: Res<Time>,
 ) {
-    for (
-        entity,
-        mut sprite,
-        mut damaged,
-        maybe_moving,
-        maybe_attacking,
-        maybe_was_moving,
-        maybe_was_attacking,
-    ) in &mut damaged_query
-    {
-        // stop movment if we damage it
-        if maybe_moving.is_some() {
-            commands
-                .entity(entity)
-                .try_remove::<Moving>()
-                .insert_if_new(WasMoving);
-        }
-
-        if maybe_attacking.is_some() {
-            commands
-                .entity(entity)
-                .try_remove::<Attacking>()
-                .insert_if_new(WasAttacking);
+    for (entity, mut damaged, mut effects, is_boss) in &mut damaged_query {
+        // getting hit knocks it senseless for a moment, bosses shrug it off
+        if damaged.is_added() && !is_boss {
+            effects.apply(StatusKind::Stun, DAMAGE_STUN_SECS, 1);
         }
 
         damaged.timer.tick(time.delta());
-        let remaining = (damaged.timer.remaining_secs() * 10.0) as u32;
-        if remaining % 2 == 0 {
-            sprite.color = Color::srgb(1.0, 0.0, 0.0);
-        } else {
-            sprite.color = Color::srgb(1.0, 1.0, 1.0);
-        }
-
         if damaged.timer.just_finished() {
-            sprite.color = Color::srgb(1.0, 1.0, 1.0);
-            if maybe_was_moving.is_some() {
-                commands
-                    .entity(entity)
-                    .remove::<Damaged>()
-                    .remove::<WasMoving>()
-                    // resume movement
-                    .insert(Moving);
-            }
-            if maybe_was_attacking.is_some() {
-                commands
-                    .entity(entity)
-                    .remove::<Damaged>()
-                    .remove::<WasAttacking>()
-                    // resume attacking
-                    .insert(Attacking);
-            }
+            commands.entity(entity).remove::<Damaged>();
         }
     }
 }
@@ -177,6 +208,109 @@ fn handle_dead(
     }
 }
 
+fn detonate_volatile_dead(
+    mut commands: Commands,
+    dead_query: Query<
+        (Entity, &GlobalTransform, &Health, &BlastedBy),
+        (With<Enemy>, With<VolatileOnDeath>, Added<Dead>),
+    >,
+    mut blast_writer: EventWriter<BlastEvent>,
+    assets: Res<VfxAssets>,
+) {
+    for (enemy, trans, health, blasted) in &dead_query {
+        // only when killed, a crash already goes off by itself
+        if health.current > 0 {
+            continue;
+        }
+        // blasted to death, carry on the chain that did it
+        let location = trans.translation().xy();
+        commands.spawn(create_explosion_vfx(&assets, location));
+        blast_writer.write(create_blast(
+            BombKind::Standard,
+            enemy,
+            location,
+            blasted.root,
+            blasted.depth + 1,
+        ));
+    }
+}
+
+/// killed splitters break apart, the pieces carry on the way the parent was going
+fn split_dead_enemies(
+    mut commands: Commands,
+    dead_query: Query<
+        (
+            &GlobalTransform,
+            &Archetype,
+            &Health,
+            &MovementConfig,
+            &TargetDistance,
+        ),
+        (With<Enemy>, Added<Dead>),
+    >,
+    level: Single<Entity, With<Level>>,
+    enemy_assets: Res<EnemyAssets>,
+    rosters: Res<Assets<EnemyRoster>>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    mut pending: ResMut<PendingSplits>,
+) {
+    for (trans, archetype, health, movement, target_dist) in &dead_query {
+        // only when killed, not when it dies after its attack
+        if health.current > 0 {
+            continue;
+        }
+        let Some(split) = archetypes
+            .get(&archetype.0)
+            .and_then(|archetype| archetype.split.as_ref())
+        else {
+            continue;
+        };
+        let Some((piece, piece_archetype)) = rosters
+            .get(&enemy_assets.roster)
+            .and_then(|roster| roster.find(&split.archetype, &archetypes))
+        else {
+            warn!("no enemy archetype named {}", split.archetype);
+            continue;
+        };
+
+        pending.0 += split.count as usize;
+        let position = trans.translation().xy();
+        for i in 0..split.count {
+            // fan the pieces out around the parent's heading
+            let offset = if split.count > 1 {
+                -split.spread / 2.0 + split.spread * i as f32 / (split.count - 1) as f32
+            } else {
+                0.0
+            };
+            let direction = Vec2::from_angle(offset.to_radians()).rotate(movement.direction);
+            let spawned = spawn_enemy(
+                &mut commands,
+                piece.clone(),
+                piece_archetype,
+                &enemy_assets,
+                position + direction * SPLIT_OFFSET,
+                direction,
+                movement.speed / SCREEN_WIDTH,
+                target_dist.0,
+            );
+            commands.entity(spawned).insert(SplitPiece);
+            commands.entity(level.entity()).add_child(spawned);
+        }
+    }
+}
+
+/// ticks off pieces once they've actually been spawned
+fn count_split_pieces(
+    piece_query: Query<(), Added<SplitPiece>>,
+    mut pending: ResMut<PendingSplits>,
+) {
+    pending.0 = pending.0.saturating_sub(piece_query.iter().count());
+}
+
+fn reset_pending_splits(mut pending: ResMut<PendingSplits>) {
+    pending.0 = 0;
+}
+
 fn switch_to_attack_player(
     mut commands: Commands,
     mut enemy_query: Query<
@@ -184,90 +318,83 @@ fn switch_to_attack_player(
             Entity,
             &Transform,
             &TargetDistance,
+            &Archetype,
             &mut AnimationConfig,
             &mut Sprite,
-            Option<&Flying>,
-            Option<&Ground>,
-            Option<&Bomber>,
         ),
         (
             With<Enemy>,
             Without<Player>,
             Without<Dead>,
             Without<Attacking>,
+            Without<WasMoving>,
+            Without<WasAttacking>,
         ),
     >,
     player: Single<&Transform, (With<Player>, Without<Enemy>)>,
+    archetypes: Res<Assets<EnemyArchetype>>,
 ) {
     let player_position = player.translation.xy();
-    for (
-        enemy,
-        enemy_trans,
-        target_dist,
-        mut anim_config,
-        mut sprite,
-        maybe_flying,
-        maybe_ground,
-        maybe_bomber,
-    ) in &mut enemy_query
+    for (enemy, enemy_trans, target_dist, archetype, mut anim_config, mut sprite) in
+        &mut enemy_query
     {
+        let Some(archetype) = archetypes.get(&archetype.0) else {
+            continue;
+        };
         let enemy_position = enemy_trans.translation.xy();
         let distance = enemy_position.distance(player_position);
-        if distance <= target_dist.0 {
-            let time_to_attack = (distance / 200.0) / 2.0;
-
-            if maybe_flying.is_some() {
-                commands
-                    .entity(enemy)
-                    .insert((
-                        Attacking,
-                        Countdown {
-                            timer: Timer::from_seconds(time_to_attack, TimerMode::Once),
-                        },
-                        TargetPosition {
-                            position: player_position,
-                        },
-                        EaseFunc(EasingCurve::new(
-                            enemy_position,
-                            player_position,
-                            EaseFunction::BackIn,
-                        )),
-                    ))
-                    .remove::<Moving>();
-            }
+        if distance > target_dist.0 {
+            continue;
+        }
 
-            if maybe_ground.is_some() {
-                commands
-                    .entity(enemy)
-                    .insert((
-                        Attacking,
-                        TargetPosition {
-                            position: player_position,
-                        },
-                    ))
-                    .remove::<Moving>();
-            }
+        let attack = archetype.attack;
+        anim_config.index = attack.index();
+        anim_config.frames = attack.frames;
+        anim_config.fps = attack.fps;
+        anim_config.timer = anim_config.timer_from_self_fps();
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = anim_config.index;
+        }
 
-            if maybe_bomber.is_some() {
-                anim_config.index = 33 * 4;
-                anim_config.fps = 2;
-                anim_config.timer = anim_config.timer_from_self_fps();
-                if let Some(atlas) = &mut sprite.texture_atlas {
-                    atlas.index = anim_config.index;
-                }
+        let mut enemy = commands.entity(enemy);
+        enemy
+            .insert((
+                Attacking,
+                TargetPosition {
+                    position: player_position,
+                },
+            ))
+            .remove::<Moving>();
 
-                commands
-                    .entity(enemy)
-                    .insert((
-                        Attacking,
-                        AttackTimer {
-                            timer: Timer::from_seconds(2.0, TimerMode::Repeating),
-                        },
-                        TargetPosition {
-                            position: player_position,
-                        },
-                    ))
-                    .remove::<Moving>();
+        match archetype.behaviour {
+            AttackBehaviour::Kamikaze { dive_speed, .. } => {
+                let time_to_attack = (distance / dive_speed) / 2.0;
+                enemy.insert((
+                    Countdown {
+                        timer: Timer::from_seconds(time_to_attack, TimerMode::Once),
+                    },
+                    EaseFunc(EasingCurve::new(
+                        enemy_position,
+                        player_position,
+                        EaseFunction::BackIn,
+                    )),
+                ));
+            }
+            AttackBehaviour::Shooter { .. } => {}
+            AttackBehaviour::Lobber { interval, .. } => {
+                enemy.insert(AttackTimer {
+                    timer: Timer::from_seconds(interval, TimerMode::Repeating),
+                });
+            }
+            AttackBehaviour::Boss => {
+                let interval = archetype
+                    .boss
+                    .as_ref()
+                    .and_then(|boss| boss.phases.first())
+                    .map_or(BOSS_FALLBACK_INTERVAL, |phase| phase.interval);
+                enemy.insert(AttackTimer {
+                    timer: Timer::from_seconds(interval, TimerMode::Repeating),
+                });
             }
         }
     }
@@ -276,32 +403,23 @@ fn switch_to_attack_player(
 fn fire_shot_at_player(
     mut commands: Commands,
     mut enemy_query: Query<
-        (
-            Entity,
-            &Transform,
-            &TargetPosition,
-            &mut AnimationConfig,
-            &mut Sprite,
-        ),
+        (Entity, &Transform, &TargetPosition, &AttackBehaviour),
         (With<Enemy>, With<Ground>, With<Attacking>, Without<Dead>),
     >,
     bullet_assets: Res<BulletAssets>,
 ) {
     //
-    for (enemy, spawn_pos, target_pos, mut anim_config, mut sprite) in enemy_query.iter_mut() {
+    for (enemy, spawn_pos, target_pos, behaviour) in enemy_query.iter_mut() {
+        let AttackBehaviour::Shooter { bullet_speed } = *behaviour else {
+            continue;
+        };
+        let start = spawn_pos.translation.xy();
         commands.spawn(create_bullet(
             &bullet_assets,
-            target_pos.position,
-            spawn_pos.translation.xy(),
-            300.0,
+            start,
+            Trajectory::aimed(start, target_pos.position, bullet_speed),
         ));
 
-        anim_config.index = 17 * 4;
-        anim_config.fps = 4;
-        anim_config.timer = anim_config.timer_from_self_fps();
-        if let Some(atlas) = &mut sprite.texture_atlas {
-            atlas.index = anim_config.index;
-        }
         commands
             .entity(enemy)
             .remove::<Attacking>()
@@ -314,27 +432,38 @@ fn fire_shot_at_player(
 fn lob_shot_at_player(
     mut commands: Commands,
     mut enemy_query: Query<
-        (&Transform, &TargetPosition, &mut AttackTimer),
+        (
+            &Transform,
+            &TargetPosition,
+            &AttackBehaviour,
+            &StatusEffects,
+            &mut AttackTimer,
+        ),
         (With<Enemy>, With<Bomber>, With<Attacking>, Without<Dead>),
     >,
     lob_assets: Res<LobShotAssets>,
     timer: Res<Time>,
 ) {
-    for (trans, target_pos, mut attack_timer) in &mut enemy_query {
+    for (trans, target_pos, behaviour, effects, mut attack_timer) in &mut enemy_query {
+        let AttackBehaviour::Lobber { speed, height, .. } = *behaviour else {
+            continue;
+        };
         //
         if attack_timer.timer.just_finished() {
             attack_timer.timer.reset();
             // spawn a lob
             commands.spawn(create_lob_shot(
                 &lob_assets,
-                200.0,
-                200.0,
+                height,
+                speed,
                 trans.translation.xy(),
                 target_pos.position,
             ));
         } else {
-            // otherwise increment timer
-            attack_timer.timer.tick(timer.delta());
+            // otherwise increment timer, slowed down by any effects
+            attack_timer
+                .timer
+                .tick(timer.delta().mul_f32(effects.speed_factor()));
         }
     }
 }
@@ -342,7 +471,15 @@ fn lob_shot_at_player(
 fn move_to_player(
     mut commands: Commands,
     mut enemy_query: Query<
-        (Entity, &mut Transform, &mut Countdown, &EaseFunc<Vec2>),
+        (
+            Entity,
+            &mut Transform,
+            &mut Countdown,
+            &EaseFunc<Vec2>,
+            &AttackBehaviour,
+            &StatusEffects,
+            Has<VolatileOnDeath>,
+        ),
         (
             With<Enemy>,
             With<Flying>,
@@ -354,9 +491,23 @@ fn move_to_player(
     player: Single<Entity, With<Player>>,
     time: Res<Time>,
     mut damage_writer: EventWriter<DamageEvent>,
+    mut blast_writer: EventWriter<BlastEvent>,
+    assets: Res<VfxAssets>,
 ) {
-    for (enemy, mut trans, mut countdown, ease) in &mut enemy_query {
-        countdown.timer.tick(time.delta());
+    for (enemy, mut trans, mut countdown, ease, behaviour, effects, is_volatile) in &mut enemy_query
+    {
+        let AttackBehaviour::Kamikaze {
+            damage,
+            impact_radius,
+            ..
+        } = *behaviour
+        else {
+            continue;
+        };
+        // slowed enemies dive slower
+        countdown
+            .timer
+            .tick(time.delta().mul_f32(effects.speed_factor()));
         if countdown.timer.just_finished() {
             // kill enemy
             commands
@@ -370,8 +521,25 @@ fn move_to_player(
             // spawn damage event
             damage_writer.write(DamageEvent {
                 target: player.entity(),
-                amount: 1,
+                amount: damage,
+                kind: DamageKind::Impact,
+                from_status: false,
             });
+
+            // the crash hits other enemies and bombs nearby too, volatile ones go up with it
+            let location = trans.translation.xy();
+            let scale = if is_volatile {
+                VOLATILE_IMPACT_SCALE
+            } else {
+                1.0
+            };
+            commands.spawn(create_explosion_vfx(&assets, location));
+            blast_writer.write(create_impact(
+                enemy,
+                location,
+                (damage as f32 * scale).ceil() as i32,
+                impact_radius * scale,
+            ));
         } else if let Some(new_pos) = ease.0.sample(countdown.timer.fraction()) {
             trans.translation = new_pos.extend(0.0);
         }
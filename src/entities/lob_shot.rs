@@ -1,17 +1,30 @@
 use crate::{
-    assets::AssetsState, components::*, events::DamageEvent, screens::Screen, AppSystems, GameplaySystems, PausableSystems
+    assets::AssetsState,
+    components::*,
+    damage::DamageKind,
+    entities::{
+        bombs::create_blast,
+        projectile::{projectile_bundle, Projectile, TossArc, Trajectory},
+    },
+    events::BlastEvent,
+    physics::{Collider, CollisionLayer, SpatialIndex},
+    vfx::{explosion::create_explosion_vfx, VfxAssets},
+    AppSystems, GameplaySystems, PausableSystems,
 };
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+/// how far a lob shot's splash reaches when it lands
+const LOB_HIT_RADIUS: f32 = 48.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<LobShotAssets>(),
     )
         .add_systems(
             Update,
-            (arc_lob_shot)
-                .in_set(AppSystems::Update)
+            detonate_blasted_lob_shots
+                .in_set(AppSystems::Events)
                 .in_set(PausableSystems)
                 .in_set(GameplaySystems),
         );
@@ -35,16 +48,24 @@ pub fn create_lob_shot(
 ) -> impl Bundle {
     let distance = spawn_pos.distance(target_pos);
     let travel_time = (distance / speed) / 2.0;
+    let toss = TossArc::new(spawn_pos, target_pos, height, 0.5, EaseFunction::CircularIn);
     (
         Name::new("Lob Shot"),
-        LobShot {
-            height,
-            timer: Timer::from_seconds(travel_time, TimerMode::Once),
-            ease_pos: EasingCurve::new(spawn_pos, target_pos, EaseFunction::Linear),
-            ease_up: EasingCurve::new(0.0, 1.0, EaseFunction::CircularOut),
-            ease_down: EasingCurve::new(1.0, 0.0, EaseFunction::CircularIn),
-        },
-        StateScoped(Screen::Gameplay),
+        LobShot,
+        projectile_bundle(
+            Projectile::new(Trajectory::arc(toss, travel_time)).hitting(
+                CollisionLayer::Player,
+                1,
+                DamageKind::Blast,
+            ),
+            spawn_pos,
+        ),
+        // only checked once it's landed, see `splash_landed_arcs`
+        Collider::circle(
+            LOB_HIT_RADIUS,
+            CollisionLayer::EnemyProjectile,
+            &[CollisionLayer::Player],
+        ),
         Sprite {
             image: assets.lob_shot.clone(),
             texture_atlas: Some(TextureAtlas {
@@ -54,54 +75,39 @@ pub fn create_lob_shot(
             custom_size: Some(Vec2::splat(96.0)),
             ..default()
         },
-        Transform::from_translation(spawn_pos.extend(0.0)),
         AnimationConfig::new(0, 7, 24),
         Animating,
-        TargetPosition {
-            position: target_pos,
-        },
     )
 }
 
-fn arc_lob_shot(
+fn detonate_blasted_lob_shots(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &mut LobShot), Without<Player>>,
-    player_query: Single<Entity, (With<Player>, Without<LobShot>)>,
-    mut damage_writer: EventWriter<DamageEvent>,
-    time: Res<Time>,
+    mut blast_reader: EventReader<BlastEvent>,
+    lob_query: Query<&GlobalTransform, With<LobShot>>,
+    index: Res<SpatialIndex>,
+    assets: Res<VfxAssets>,
 ) {
-    for (entity, mut transform, mut lob_shot) in &mut query {
-        if lob_shot.timer.just_finished() {
-            // destroy
-            commands.entity(entity).despawn();
-            // inform player of damage
-            damage_writer.write(DamageEvent {
-                target: player_query.entity(),
-                amount: 1,
-            });
-
-        } else {
-            let fraction = lob_shot.timer.fraction();
-            let mut new_pos = lob_shot.ease_pos.sample_clamped(fraction);
-
-            if fraction < 0.5 {
-                let up_frac = fraction / 0.5;
-                // going up
-                let up = lob_shot.ease_up.sample_clamped(up_frac) * lob_shot.height;
-                new_pos.y += up;
-            } else {
-                // going down
-                let down_frac = (fraction - 0.5) / 0.5;
-                // going up
-                let down = lob_shot.ease_down.sample_clamped(down_frac) * lob_shot.height;
-                new_pos.y += down;
+    let mut caught: Vec<(Entity, Vec2, Entity, u32)> = vec![];
+    for blast in blast_reader.read() {
+        for lob in index.nearby(blast.location, blast.damage_range) {
+            let Ok(lob_trans) = lob_query.get(lob) else {
+                continue;
+            };
+            let location = lob_trans.translation().xy();
+            if blast.source != lob
+                && location.distance(blast.location) <= blast.damage_range
+                && !caught.iter().any(|(other, ..)| *other == lob)
+            {
+                caught.push((lob, location, blast.root, blast.depth + 1));
             }
-
-            // update transform
-            transform.translation = new_pos.extend(0.0);
-
-            // update timer
-            lob_shot.timer.tick(time.delta());
         }
     }
+
+    // caught shots go off like bombs and carry on the chain
+    for (lob, location, root, depth) in caught {
+        commands.entity(lob).try_despawn();
+        commands.spawn(create_explosion_vfx(&assets, location));
+        // sent through commands since we're still reading blasts
+        commands.send_event(create_blast(BombKind::Standard, lob, location, root, depth));
+    }
 }
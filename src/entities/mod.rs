@@ -1,11 +1,25 @@
 use bevy::prelude::*;
 
+pub mod archetype;
 pub mod bombs;
+pub mod boss;
 pub mod bullet;
 pub mod enemy;
 pub mod lob_shot;
+pub mod napalm;
 pub mod player;
+pub mod projectile;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((bombs::plugin, bullet::plugin, lob_shot::plugin, player::plugin, enemy::plugin));
+    app.add_plugins((
+        archetype::plugin,
+        bombs::plugin,
+        boss::plugin,
+        bullet::plugin,
+        lob_shot::plugin,
+        napalm::plugin,
+        player::plugin,
+        projectile::plugin,
+        enemy::plugin,
+    ));
 }
@@ -0,0 +1,81 @@
+use crate::{
+    components::*, physics::SpatialIndex, screens::Screen, vfx::VfxAssets, AppSystems,
+    GameplaySystems, PausableSystems,
+};
+use bevy::prelude::*;
+
+/// how long enemies keep burning after leaving the napalm
+const BURN_SECS: f32 = 1.5;
+/// how long a patch burns before it's gone
+const NAPALM_SECS: f32 = 3.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        burn_enemies_in_napalm
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+pub fn create_napalm_patch(
+    assets: &VfxAssets,
+    location: Vec2,
+    radius: f32,
+    damage: i32,
+) -> impl Bundle {
+    (
+        Name::new("Napalm"),
+        Napalm {
+            tick: Timer::from_seconds(0.5, TimerMode::Repeating),
+            radius,
+            damage,
+        },
+        Lifetime::from_seconds(NAPALM_SECS),
+        StateScoped(Screen::Gameplay),
+        Sprite {
+            image: assets.explosion.clone(),
+            texture_atlas: Some(TextureAtlas {
+                index: 0,
+                layout: assets.explosion_layout.clone(),
+            }),
+            color: Color::srgba(1.0, 0.6, 0.2, 0.6),
+            custom_size: Some(Vec2::splat(radius * 2.0)),
+            ..default()
+        },
+        Transform::from_translation(location.extend(0.0)),
+        AnimationConfig::new(0, 6, 12),
+        Animating,
+    )
+}
+
+fn burn_enemies_in_napalm(
+    mut napalm_query: Query<(&GlobalTransform, &mut Napalm, &Lifetime, &mut Sprite)>,
+    mut enemy_query: Query<
+        (&GlobalTransform, &mut StatusEffects),
+        (With<Enemy>, With<Damageable>, Without<Dead>),
+    >,
+    index: Res<SpatialIndex>,
+    time: Res<Time>,
+) {
+    for (napalm_trans, mut napalm, lifetime, mut sprite) in &mut napalm_query {
+        napalm.tick.tick(time.delta());
+
+        // burn out slowly
+        let frac = lifetime.0.fraction_remaining();
+        sprite.color = Color::srgba(1.0, 0.6, 0.2, 0.6 * frac);
+
+        if napalm.tick.just_finished() {
+            let location = napalm_trans.translation().xy();
+            for enemy in index.nearby(location, napalm.radius) {
+                let Ok((enemy_trans, mut effects)) = enemy_query.get_mut(enemy) else {
+                    continue;
+                };
+                if enemy_trans.translation().xy().distance(location) <= napalm.radius {
+                    effects.apply(StatusKind::Burn, BURN_SECS, napalm.damage as u32);
+                }
+            }
+        }
+    }
+}
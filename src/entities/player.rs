@@ -2,22 +2,41 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
 use crate::{
-    assets::AssetsState, components::*, constants::SCREEN_WIDTH, menus::Menu, screens::Screen,
-    waves::WaveState, AppSystems, GameplaySystems, PausableSystems, Pause,
+    assets::AssetsState,
+    components::*,
+    constants::SCREEN_WIDTH,
+    menus::Menu,
+    physics::{Collider, CollisionLayer, FitToSprite},
+    screens::Screen,
+    waves::WaveState,
+    AppSystems, GameplaySystems, PausableSystems, Pause,
 };
 
+/// fraction of the sprite that can be hit, bullets used to have to get within 15 of the middle
+const PLAYER_HITBOX: f32 = 0.33;
+
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<CharacterAssets>(),
     );
     app.add_systems(
+        Update,
+        regen_bomb_ammo
+            .in_set(AppSystems::TickTimers)
+            .in_set(GameplaySystems)
+            .in_set(PausableSystems),
+    )
+    .add_systems(
         Update,
         (check_if_player_dead)
             .in_set(AppSystems::Update)
             .in_set(GameplaySystems)
             .in_set(PausableSystems),
     )
-    .add_systems(OnEnter(WaveState::Announce), reset_player_hp);
+    .add_systems(
+        OnEnter(WaveState::Announce),
+        (reset_player_hp, refill_bomb_ammo),
+    );
 }
 
 #[derive(AssetCollection, Resource)]
@@ -36,13 +55,20 @@ pub fn create_player_character(assets: &CharacterAssets, health: i32) -> impl Bu
         Name::new("Character"),
         Animating,
         AnimationConfig::new(start_index, 4, 4),
+        BombAmmo::new(5, 1.5),
         Character,
         Damageable,
+        DetonationMode::Fuse,
         Health {
             current: health,
             max: health,
         },
         Player,
+        (
+            Collider::circle(0.0, CollisionLayer::Player, &[]),
+            FitToSprite(PLAYER_HITBOX),
+        ),
+        SelectedBomb(BombKind::Standard),
         Sprite {
             image: assets.character_idle.clone(),
             texture_atlas: Some(TextureAtlas {
@@ -72,3 +98,18 @@ fn check_if_player_dead(
 pub fn reset_player_hp(mut player_health: Single<&mut Health, (With<Player>, Without<Dead>)>) {
     player_health.current = player_health.max;
 }
+
+pub fn refill_bomb_ammo(mut ammo: Single<&mut BombAmmo, (With<Player>, Without<Dead>)>) {
+    ammo.refill();
+}
+
+fn regen_bomb_ammo(mut ammo: Single<&mut BombAmmo, With<Player>>, time: Res<Time>) {
+    if ammo.is_full() {
+        return;
+    }
+
+    ammo.regen.tick(time.delta());
+    if ammo.regen.just_finished() {
+        ammo.current += 1;
+    }
+}
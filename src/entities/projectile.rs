@@ -0,0 +1,313 @@
+//! Anything flying through the air: bullets, lob shots and tossed bombs.
+//! How a projectile moves is down to its [`Trajectory`], what it hits is down to its targets.
+
+use bevy::prelude::*;
+
+use crate::{
+    components::*,
+    constants::{SCREEN_HALF_HEIGHT, SCREEN_HALF_WIDTH},
+    damage::DamageKind,
+    events::{CollisionStarted, DamageEvent, ProjectileLanded},
+    physics::{Collider, CollisionLayer, Contacts},
+    screens::Screen,
+    AppSystems, GameplaySystems, PausableSystems,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (fly_projectiles, (hit_targets, splash_landed_arcs))
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+/// Something in flight, moved along its trajectory until it hits or lands.
+#[derive(Component)]
+pub struct Projectile {
+    pub trajectory: Trajectory,
+    /// what it can hit, flies through everything if `None`
+    pub targets: Option<CollisionLayer>,
+    pub damage: i32,
+    pub kind: DamageKind,
+}
+
+impl Projectile {
+    pub fn new(trajectory: Trajectory) -> Self {
+        Self {
+            trajectory,
+            targets: None,
+            damage: 0,
+            kind: DamageKind::Impact,
+        }
+    }
+
+    pub fn hitting(mut self, targets: CollisionLayer, damage: i32, kind: DamageKind) -> Self {
+        self.targets = Some(targets);
+        self.damage = damage;
+        self.kind = kind;
+        self
+    }
+}
+
+/// How a projectile gets about.
+#[derive(Clone)]
+pub enum Trajectory {
+    /// straight on at a fixed velocity
+    Linear { velocity: Vec2 },
+    /// up and over, landing where it was aimed once the flight is over
+    Arc { toss: TossArc, flight: Timer },
+    /// turns towards a target, at most `turn_rate` radians per second
+    Homing {
+        target: Entity,
+        velocity: Vec2,
+        turn_rate: f32,
+    },
+    /// straight on, bouncing off the screen edges until it runs out of bounces
+    Bouncing { velocity: Vec2, bounces: u32 },
+}
+
+impl Trajectory {
+    /// a straight shot from `from` towards `to`
+    pub fn aimed(from: Vec2, to: Vec2, speed: f32) -> Self {
+        Trajectory::Linear {
+            velocity: (to - from).normalize_or(Vec2::NEG_X) * speed,
+        }
+    }
+
+    pub fn arc(toss: TossArc, secs: f32) -> Self {
+        Trajectory::Arc {
+            toss,
+            flight: Timer::from_seconds(secs, TimerMode::Once),
+        }
+    }
+
+    /// still on the way up, only arcs ever are
+    pub fn is_rising(&self) -> bool {
+        match self {
+            Trajectory::Arc { toss, flight } => flight.fraction() < toss.apex,
+            _ => false,
+        }
+    }
+
+    /// whether it's come down yet, only arcs ever do
+    pub fn has_landed(&self) -> bool {
+        match self {
+            Trajectory::Arc { flight, .. } => flight.finished(),
+            _ => false,
+        }
+    }
+
+    fn velocity(&self) -> Option<Vec2> {
+        match self {
+            Trajectory::Linear { velocity }
+            | Trajectory::Homing { velocity, .. }
+            | Trajectory::Bouncing { velocity, .. } => Some(*velocity),
+            Trajectory::Arc { .. } => None,
+        }
+    }
+}
+
+/// The path of something thrown: straight along the ground, with a rise and a fall on top.
+#[derive(Clone)]
+pub struct TossArc {
+    pub ground: EasingCurve<Vec2>,
+    pub rise: EasingCurve<f32>,
+    pub fall: EasingCurve<f32>,
+    pub height: f32,
+    /// fraction of the flight spent rising before it falls
+    pub apex: f32,
+}
+
+impl TossArc {
+    pub fn new(from: Vec2, to: Vec2, height: f32, apex: f32, fall: EaseFunction) -> Self {
+        Self {
+            ground: EasingCurve::new(from, to, EaseFunction::Linear),
+            rise: EasingCurve::new(0.0, 1.0, EaseFunction::CircularOut),
+            fall: EasingCurve::new(1.0, 0.0, fall),
+            height,
+            apex,
+        }
+    }
+
+    /// where it is `fraction` of the way through the flight
+    pub fn sample(&self, fraction: f32) -> Vec2 {
+        let mut position = self.ground.sample_clamped(fraction);
+        if fraction < self.apex {
+            let up_frac = fraction / self.apex;
+            position.y += self.rise.sample_clamped(up_frac) * self.height;
+        } else {
+            let down_frac = (fraction - self.apex) / (1.0 - self.apex);
+            position.y += self.fall.sample_clamped(down_frac) * self.height;
+        }
+        position
+    }
+
+    /// where it comes down
+    pub fn end(&self) -> Vec2 {
+        self.ground.sample_clamped(1.0)
+    }
+}
+
+/// everything a projectile needs besides its sprite
+pub fn projectile_bundle(projectile: Projectile, position: Vec2) -> impl Bundle {
+    // sprites point up, turn them to face the way they're going
+    let rotation = projectile
+        .trajectory
+        .velocity()
+        .map_or(Quat::IDENTITY, |velocity| {
+            Quat::from_rotation_z(Vec2::Y.angle_to(velocity))
+        });
+    (
+        projectile,
+        StateScoped(Screen::Gameplay),
+        Transform::from_translation(position.extend(0.0)).with_rotation(rotation),
+    )
+}
+
+fn fly_projectiles(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Projectile)>,
+    target_query: Query<&GlobalTransform>,
+    mut landed_writer: EventWriter<ProjectileLanded>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut transform, mut projectile) in &mut query {
+        let position = transform.translation.xy();
+        let has_targets = projectile.targets.is_some();
+        match &mut projectile.trajectory {
+            Trajectory::Linear { velocity } => {
+                transform.translation += (*velocity * delta).extend(0.0);
+            }
+            Trajectory::Arc { toss, flight } => {
+                flight.tick(time.delta());
+                transform.translation = toss.sample(flight.fraction()).extend(0.0);
+                if flight.just_finished() {
+                    landed_writer.write(ProjectileLanded {
+                        projectile: entity,
+                        position: toss.end(),
+                    });
+                    // nothing to hit, so it just comes to rest
+                    if !has_targets {
+                        commands.entity(entity).remove::<Projectile>();
+                    }
+                }
+            }
+            Trajectory::Homing {
+                target,
+                velocity,
+                turn_rate,
+            } => {
+                // lost its target, carry on straight
+                if let Ok(target_trans) = target_query.get(*target) {
+                    let wanted = target_trans.translation().xy() - position;
+                    let max_turn = *turn_rate * delta;
+                    let turn = velocity.angle_to(wanted).clamp(-max_turn, max_turn);
+                    *velocity = Vec2::from_angle(turn).rotate(*velocity);
+                }
+                transform.translation += (*velocity * delta).extend(0.0);
+            }
+            Trajectory::Bouncing { velocity, bounces } => {
+                transform.translation += (*velocity * delta).extend(0.0);
+                let position = transform.translation.xy();
+                // only bounce when heading further out, so it can't get stuck on an edge
+                if *bounces > 0
+                    && position.x.abs() > SCREEN_HALF_WIDTH
+                    && position.x.signum() == velocity.x.signum()
+                {
+                    velocity.x = -velocity.x;
+                    *bounces -= 1;
+                }
+                if *bounces > 0
+                    && position.y.abs() > SCREEN_HALF_HEIGHT
+                    && position.y.signum() == velocity.y.signum()
+                {
+                    velocity.y = -velocity.y;
+                    *bounces -= 1;
+                }
+            }
+        }
+
+        if let Some(velocity) = projectile.trajectory.velocity() {
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_to(velocity));
+        }
+    }
+}
+
+/// straight shots hit the first target they run into
+fn hit_targets(
+    mut commands: Commands,
+    mut collision_reader: EventReader<CollisionStarted>,
+    projectile_query: Query<&Projectile>,
+    target_query: Query<&Collider, (With<Damageable>, Without<Dead>)>,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    let mut spent: Vec<Entity> = vec![];
+    for collision in collision_reader.read() {
+        for (shot, target) in [(collision.a, collision.b), (collision.b, collision.a)] {
+            let Ok(projectile) = projectile_query.get(shot) else {
+                continue;
+            };
+            // arcs fly over everything, see `splash_landed_arcs`
+            if matches!(projectile.trajectory, Trajectory::Arc { .. }) || spent.contains(&shot) {
+                continue;
+            }
+            let Ok(collider) = target_query.get(target) else {
+                continue;
+            };
+            if projectile.targets != Some(collider.layer) {
+                continue;
+            }
+
+            damage_writer.write(DamageEvent {
+                target,
+                amount: projectile.damage,
+                kind: projectile.kind,
+                from_status: false,
+            });
+            commands.entity(shot).try_despawn();
+            spent.push(shot);
+        }
+    }
+}
+
+/// arcs hit whatever their collider is touching once they've landed, hit or miss they're gone
+fn splash_landed_arcs(
+    mut commands: Commands,
+    projectile_query: Query<(Entity, &Projectile)>,
+    target_query: Query<&Collider, (With<Damageable>, Without<Dead>)>,
+    contacts: Res<Contacts>,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    for (entity, projectile) in &projectile_query {
+        let Some(targets) = projectile.targets else {
+            continue;
+        };
+        // contacts are from the start of the frame, give them one to catch up with the landing
+        let just_landed = matches!(
+            &projectile.trajectory,
+            Trajectory::Arc { flight, .. } if flight.just_finished()
+        );
+        if !projectile.trajectory.has_landed() || just_landed {
+            continue;
+        }
+
+        for target in contacts.touching(entity) {
+            let Ok(collider) = target_query.get(target) else {
+                continue;
+            };
+            if collider.layer == targets {
+                damage_writer.write(DamageEvent {
+                    target,
+                    amount: projectile.damage,
+                    kind: projectile.kind,
+                    from_status: false,
+                });
+            }
+        }
+        commands.entity(entity).try_despawn();
+    }
+}
@@ -1,10 +1,16 @@
 use bevy::prelude::*;
 
+use crate::{components::StatusKind, damage::DamageKind};
+
 pub(super) fn plugin(app: &mut App) {
     // register events
     app.add_event::<BlastEvent>()
+        .add_event::<ChainResolved>()
+        .add_event::<CollisionEnded>()
+        .add_event::<CollisionStarted>()
         .add_event::<DamageEvent>()
         .add_event::<EnemyDiedEvent>()
+        .add_event::<ProjectileLanded>()
         .add_event::<SpawningDoneEvent>();
 }
 
@@ -12,17 +18,65 @@ pub(super) fn plugin(app: &mut App) {
 pub struct BlastEvent {
     pub source: Entity,
     pub location: Vec2,
+    /// radius within which other bombs are set off
     pub range: f32,
+    /// radius within which enemies are damaged
+    pub damage_range: f32,
+    /// damage dealt at the centre of the blast, tapering towards the edge
+    pub damage: i32,
+    pub damage_kind: DamageKind,
+    /// effects left on enemies in the damage range, with their duration
+    pub statuses: &'static [(StatusKind, f32)],
+    /// units per second enemies at the centre are pushed away
+    pub knockback: f32,
+    /// the bomb that started this chain reaction
+    pub root: Entity,
+    /// how many links down the chain this blast is, root is 0
+    pub depth: u32,
+}
+
+/// sent once a chain reaction has fully played out
+#[derive(Event)]
+pub struct ChainResolved {
+    pub root: Entity,
+    pub depth: u32,
+    pub bombs: u32,
+    pub kills: u32,
+    pub points: u64,
+}
+
+/// two colliders started overlapping, smaller entity first
+#[derive(Event)]
+pub struct CollisionStarted {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+/// two colliders stopped overlapping, or one of them is gone
+#[derive(Event)]
+pub struct CollisionEnded {
+    pub a: Entity,
+    pub b: Entity,
 }
 
 #[derive(Event)]
 pub struct DamageEvent {
     pub target: Entity,
     pub amount: i32,
+    pub kind: DamageKind,
+    /// a status effect wearing it down rather than a fresh hit
+    pub from_status: bool,
 }
 
 #[derive(Event)]
 pub struct EnemyDiedEvent;
 
+/// sent when an arcing projectile comes down
+#[derive(Event)]
+pub struct ProjectileLanded {
+    pub projectile: Entity,
+    pub position: Vec2,
+}
+
 #[derive(Event)]
 pub struct SpawningDoneEvent;
@@ -14,20 +14,26 @@ pub(super) fn plugin(app: &mut App) {
 fn apply_damage_to_hp(
     mut commands: Commands,
     mut damage_events: EventReader<DamageEvent>,
-    mut query: Query<(Entity, &mut Health), With<Damageable>>,
+    mut query: Query<(Entity, &mut Health, Option<&Armor>, Option<&Resistances>), With<Damageable>>,
 ) -> Result {
     if !damage_events.is_empty() {
         for damage in damage_events.read() {
-            let (entity, mut health) = query.get_mut(damage.target)?;
-            health.current -= damage.amount;
-            info!("damaging hp: {} {}", health.current, damage.amount);
+            let (entity, mut health, maybe_armor, maybe_resistances) =
+                query.get_mut(damage.target)?;
+            let amount = mitigate(damage, maybe_armor, maybe_resistances);
+            if amount <= 0 {
+                // shrugged it off entirely
+                continue;
+            }
+            health.current -= amount;
+            info!("damaging hp: {} {}", health.current, amount);
             if health.current <= 0 {
                 // ded
                 commands.entity(entity).insert_if_new(Dead {
                     timer: Timer::from_seconds(0.5, TimerMode::Once),
                 });
-            } else {
-                // for damage effects later
+            } else if !damage.from_status {
+                // for damage effects later, status ticks already show in the tint
                 commands.entity(entity).insert_if_new(Damaged {
                     timer: Timer::from_seconds(0.5, TimerMode::Once),
                 });
@@ -36,3 +42,23 @@ fn apply_damage_to_hp(
     }
     Ok(())
 }
+
+/// how much of a hit gets through resistances and then armor
+fn mitigate(
+    damage: &DamageEvent,
+    maybe_armor: Option<&Armor>,
+    maybe_resistances: Option<&Resistances>,
+) -> i32 {
+    let resisted = maybe_resistances
+        .and_then(|resistances| resistances.0.get(&damage.kind))
+        .copied()
+        .unwrap_or(0.0);
+    let amount = (damage.amount as f32 * (1.0 - resisted)).round() as i32;
+    if amount <= 0 {
+        return 0;
+    }
+
+    // armor blunts a hit but never stops it outright
+    let armor = maybe_armor.map_or(0, |armor| armor.0);
+    (amount - armor).max(1)
+}
@@ -8,6 +8,7 @@ mod animation;
 mod assets;
 mod audio;
 mod background;
+mod bounds;
 mod components;
 mod constants;
 mod controlls;
@@ -21,8 +22,10 @@ mod menus;
 mod movement;
 mod physics;
 mod random;
+mod score;
 mod screens;
 mod spawners;
+mod status;
 mod theme;
 mod ui;
 mod vfx;
@@ -83,6 +86,7 @@ impl Plugin for AppPlugin {
             animation::plugin,
             audio::plugin,
             background::plugin,
+            bounds::plugin,
             components::plugin,
             controlls::plugin,
             damage::plugin,
@@ -94,8 +98,10 @@ impl Plugin for AppPlugin {
         app.add_plugins((
             menus::plugin,
             movement::plugin,
+            score::plugin,
             screens::plugin,
             spawners::plugin,
+            status::plugin,
             theme::plugin,
             ui::plugin,
             vfx::plugin,
@@ -2,19 +2,23 @@
 
 use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{menus::Menu, score::Score, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::GameOver), spawn_game_over_menu);
 }
 
-fn spawn_game_over_menu(mut commands: Commands) {
+fn spawn_game_over_menu(mut commands: Commands, score: Res<Score>) {
+    let total = score.total;
+    let best_depth = score.best_depth;
     commands.spawn((
         widget::ui_root("GameOver Menu"),
         GlobalZIndex(2),
         StateScoped(Menu::GameOver),
         children![
             widget::header("Game Over :("),
+            widget::label(format!("Score: {total}")),
+            widget::label(format!("Longest Chain: {best_depth}")),
             widget::button("Quit to title", quit_to_title),
         ],
     ));
@@ -2,10 +2,33 @@ use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{components::*, AppSystems, GameplaySystems, PausableSystems};
 
+/// how close counts as having reached a waypoint
+const WAYPOINT_REACHED: f32 = 12.0;
+/// how hard formation members pull back into their slot, per unit of error
+const FORMATION_STIFFNESS: f32 = 2.0;
+/// fastest a formation member will go to catch up, as a multiple of its own speed
+const FORMATION_CATCH_UP: f32 = 1.5;
+/// how far away flockmates are noticed
+const FLOCK_RADIUS: f32 = 120.0;
+/// flockmates closer than this get pushed away from
+const FLOCK_SEPARATION: f32 = 40.0;
+/// weights of the boids rules, and of the flock's own heading
+const SEPARATION_WEIGHT: f32 = 1.5;
+const ALIGNMENT_WEIGHT: f32 = 1.0;
+const COHESION_WEIGHT: f32 = 0.8;
+const HEADING_WEIGHT: f32 = 1.2;
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (apply_movement, apply_screen_wrap)
+        (
+            follow_path,
+            (keep_formation, flock),
+            apply_movement,
+            apply_knockback,
+            apply_screen_wrap,
+        )
+            .chain()
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
@@ -13,16 +36,181 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 fn apply_movement(
-    mut query: Query<(&mut Transform, &MovementConfig), With<Moving>>,
+    mut query: Query<(&mut Transform, &MovementConfig, Option<&StatusEffects>), With<Moving>>,
     time: Res<Time>,
 ) {
-    for (mut transform, config) in &mut query {
-        let unit_rate = time.delta_secs() * config.speed;
+    for (mut transform, config, maybe_effects) in &mut query {
+        let speed = config.speed * maybe_effects.map_or(1.0, |effects| effects.speed_factor());
+        let unit_rate = time.delta_secs() * speed;
         let delta = unit_rate * config.direction;
         transform.translation += delta.extend(0.0);
     }
 }
 
+/// steers along a path one waypoint at a time, then carries on the last heading
+fn follow_path(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut MovementConfig,
+            &mut Path,
+            Option<&mut Sprite>,
+        ),
+        With<Moving>,
+    >,
+) {
+    for (entity, transform, mut config, mut path, maybe_sprite) in &mut query {
+        let position = transform.translation.xy();
+        // skip past any waypoints we've reached
+        while path
+            .target()
+            .is_some_and(|target| target.distance(position) <= WAYPOINT_REACHED)
+        {
+            path.next += 1;
+        }
+
+        let Some(target) = path.target() else {
+            commands.entity(entity).remove::<Path>();
+            continue;
+        };
+        config.direction = (target - position).normalize_or(config.direction);
+        // face the way we're walking
+        if let Some(mut sprite) = maybe_sprite {
+            sprite.flip_x = config.direction.x > 0.0;
+        }
+    }
+}
+
+/// steers formation members towards their slot next to the leader
+fn keep_formation(
+    mut commands: Commands,
+    mut member_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut MovementConfig,
+            &FormationMember,
+            Option<&mut Sprite>,
+        ),
+        With<Moving>,
+    >,
+    leader_query: Query<
+        (&Transform, &MovementConfig, Has<Moving>),
+        (Without<FormationMember>, Without<Dead>),
+    >,
+) {
+    for (entity, transform, mut config, member, maybe_sprite) in &mut member_query {
+        let Ok((leader_transform, leader_config, leader_moving)) = leader_query.get(member.leader)
+        else {
+            // leader is gone, every enemy for themselves
+            config.speed = member.speed;
+            commands.entity(entity).remove::<FormationMember>();
+            continue;
+        };
+
+        let slot =
+            leader_transform.translation.xy() + leader_config.direction.rotate(member.offset);
+        let leader_velocity = if leader_moving {
+            leader_config.direction * leader_config.speed
+        } else {
+            Vec2::ZERO
+        };
+        // keep pace with the leader and close the gap to the slot
+        let velocity = leader_velocity + (slot - transform.translation.xy()) * FORMATION_STIFFNESS;
+        let max_speed = member.speed.max(leader_config.speed) * FORMATION_CATCH_UP;
+        let Some(direction) = velocity.try_normalize() else {
+            continue;
+        };
+        config.direction = direction;
+        config.speed = velocity.length().min(max_speed);
+        if let Some(mut sprite) = maybe_sprite {
+            sprite.flip_x = config.direction.x > 0.0;
+        }
+    }
+}
+
+/// boids: keep apart, line up with and close in on nearby flockmates,
+/// while still heading where the flock wants to go
+fn flock(
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut MovementConfig,
+            &Flocking,
+            Option<&Path>,
+            Option<&mut Sprite>,
+        ),
+        (With<Moving>, Without<FormationMember>),
+    >,
+) {
+    let boids: Vec<(Entity, Vec2, Vec2)> = query
+        .iter()
+        .map(|(entity, transform, config, ..)| {
+            (entity, transform.translation.xy(), config.direction)
+        })
+        .collect();
+
+    for (entity, transform, mut config, flocking, maybe_path, maybe_sprite) in &mut query {
+        let position = transform.translation.xy();
+        let mut separation = Vec2::ZERO;
+        let mut alignment = Vec2::ZERO;
+        let mut centre = Vec2::ZERO;
+        let mut neighbours = 0;
+        for (other, other_position, other_direction) in &boids {
+            let distance = position.distance(*other_position);
+            if *other == entity || distance > FLOCK_RADIUS {
+                continue;
+            }
+            if distance < FLOCK_SEPARATION {
+                // push away harder the closer they are
+                separation += (position - *other_position).normalize_or_zero()
+                    * (1.0 - distance / FLOCK_SEPARATION);
+            }
+            alignment += *other_direction;
+            centre += *other_position;
+            neighbours += 1;
+        }
+
+        // a path takes over from the flock's heading
+        let heading = maybe_path
+            .and_then(|path| path.target())
+            .map_or(flocking.heading, |target| {
+                (target - position).normalize_or(flocking.heading)
+            });
+        let mut steer = heading * HEADING_WEIGHT;
+        if neighbours > 0 {
+            let cohesion = (centre / neighbours as f32 - position).normalize_or_zero();
+            steer += separation * SEPARATION_WEIGHT
+                + alignment.normalize_or_zero() * ALIGNMENT_WEIGHT
+                + cohesion * COHESION_WEIGHT;
+        }
+        config.direction = steer.normalize_or(config.direction);
+        if let Some(mut sprite) = maybe_sprite {
+            sprite.flip_x = config.direction.x > 0.0;
+        }
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Knockback)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut knockback) in &mut query {
+        knockback.timer.tick(time.delta());
+        // ease out the push as the timer runs down
+        let delta = knockback.velocity * knockback.timer.fraction_remaining() * time.delta_secs();
+        transform.translation += delta.extend(0.0);
+
+        if knockback.timer.just_finished() {
+            commands.entity(entity).remove::<Knockback>();
+        }
+    }
+}
+
 fn apply_screen_wrap(
     window: Single<&Window, With<PrimaryWindow>>,
     mut wrap_query: Query<&mut Transform, With<ScreenWrap>>,
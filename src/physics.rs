@@ -1,6 +1,283 @@
 // use avian2d::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    components::{Blastable, Bomb},
+    entities::projectile::Projectile,
+    events::{CollisionEnded, CollisionStarted},
+    AppSystems, GameplaySystems, PausableSystems,
+};
+
+/// side of a spatial index cell, around the size of a root blast
+const CELL_SIZE: f32 = 64.0;
 
 pub(super) fn plugin(app: &mut App) {
     // app.add_plugins(PhysicsPlugins::default());
+    app.init_resource::<SpatialIndex>()
+        .init_resource::<Contacts>()
+        .add_systems(
+            Update,
+            (
+                fit_colliders_to_sprites,
+                rebuild_spatial_index,
+                detect_collisions,
+                track_contacts,
+            )
+                .chain()
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems)
+                .in_set(GameplaySystems),
+        );
+}
+
+/// What a collider belongs to, and so what it can run into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionLayer {
+    Player,
+    Enemy,
+    Bomb,
+    EnemyProjectile,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ColliderShape {
+    Circle { radius: f32 },
+    Aabb { half_size: Vec2 },
+}
+
+impl ColliderShape {
+    /// radius of a circle around the whole shape
+    fn extent(&self) -> f32 {
+        match self {
+            ColliderShape::Circle { radius } => *radius,
+            ColliderShape::Aabb { half_size } => half_size.length(),
+        }
+    }
+
+    fn overlaps(&self, position: Vec2, other: &ColliderShape, other_position: Vec2) -> bool {
+        match (*self, *other) {
+            (
+                ColliderShape::Circle { radius },
+                ColliderShape::Circle {
+                    radius: other_radius,
+                },
+            ) => position.distance(other_position) <= radius + other_radius,
+            (
+                ColliderShape::Aabb { half_size },
+                ColliderShape::Aabb {
+                    half_size: other_half,
+                },
+            ) => {
+                let gap = (position - other_position).abs();
+                gap.x <= half_size.x + other_half.x && gap.y <= half_size.y + other_half.y
+            }
+            (ColliderShape::Circle { radius }, ColliderShape::Aabb { half_size }) => {
+                circle_touches_box(position, radius, other_position, half_size)
+            }
+            (ColliderShape::Aabb { half_size }, ColliderShape::Circle { radius }) => {
+                circle_touches_box(other_position, radius, position, half_size)
+            }
+        }
+    }
+}
+
+fn circle_touches_box(centre: Vec2, radius: f32, box_centre: Vec2, half_size: Vec2) -> bool {
+    let closest = centre.clamp(box_centre - half_size, box_centre + half_size);
+    centre.distance(closest) <= radius
+}
+
+/// A shape that reports overlaps with colliders on the layers in its mask.
+#[derive(Component, Clone, Debug)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    pub layer: CollisionLayer,
+    /// layers this one reports overlaps with, others still see it through theirs
+    pub mask: &'static [CollisionLayer],
+}
+
+impl Collider {
+    pub fn circle(radius: f32, layer: CollisionLayer, mask: &'static [CollisionLayer]) -> Self {
+        Self {
+            shape: ColliderShape::Circle { radius },
+            layer,
+            mask,
+        }
+    }
+
+    pub fn aabb(half_size: Vec2, layer: CollisionLayer, mask: &'static [CollisionLayer]) -> Self {
+        Self {
+            shape: ColliderShape::Aabb { half_size },
+            layer,
+            mask,
+        }
+    }
+
+    fn interacts_with(&self, other: &Collider) -> bool {
+        self.mask.contains(&other.layer) || other.mask.contains(&self.layer)
+    }
+}
+
+/// keeps a collider sized to this fraction of the sprite's `custom_size`
+#[derive(Component)]
+pub struct FitToSprite(pub f32);
+
+/// Every pair of colliders overlapping right now, smaller entity first.
+/// Kept up to date from the collision events, so it always agrees with them.
+#[derive(Resource, Default)]
+pub struct Contacts(HashSet<(Entity, Entity)>);
+
+impl Contacts {
+    /// everything overlapping `entity`
+    pub fn touching(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().filter_map(move |&(a, b)| {
+            if a == entity {
+                Some(b)
+            } else if b == entity {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A grid of everything blasts and shots care about, so they only look at what's close by.
+/// Rebuilt at the start of every frame, so it can be a frame behind on positions.
+#[derive(Resource, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl SpatialIndex {
+    fn cell(position: Vec2) -> IVec2 {
+        (position / CELL_SIZE).floor().as_ivec2()
+    }
+
+    pub fn clear(&mut self) {
+        // keep the allocations around for next frame
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        self.cells
+            .entry(Self::cell(position))
+            .or_default()
+            .push(entity);
+    }
+
+    /// everything in the cells touching a circle, callers still need to check the distance
+    pub fn nearby(&self, centre: Vec2, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let min = Self::cell(centre - radius);
+        let max = Self::cell(centre + radius);
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    query: Query<
+        (Entity, &GlobalTransform),
+        Or<(
+            With<Blastable>,
+            With<Bomb>,
+            With<Projectile>,
+            With<Collider>,
+        )>,
+    >,
+) {
+    index.clear();
+    for (entity, trans) in &query {
+        index.insert(entity, trans.translation().xy());
+    }
+}
+
+fn fit_colliders_to_sprites(
+    mut query: Query<
+        (&mut Collider, &Sprite, &FitToSprite),
+        Or<(Added<FitToSprite>, Changed<Sprite>)>,
+    >,
+) {
+    for (mut collider, sprite, fit) in &mut query {
+        let Some(size) = sprite.custom_size else {
+            continue;
+        };
+        let half_size = size / 2.0 * fit.0;
+        collider.shape = match collider.shape {
+            ColliderShape::Circle { .. } => ColliderShape::Circle {
+                radius: half_size.min_element(),
+            },
+            ColliderShape::Aabb { .. } => ColliderShape::Aabb { half_size },
+        };
+    }
+}
+
+/// narrow phase over whatever the spatial index says is close, then reports what changed
+fn detect_collisions(
+    query: Query<(Entity, &GlobalTransform, &Collider)>,
+    index: Res<SpatialIndex>,
+    mut previous: Local<HashSet<(Entity, Entity)>>,
+    mut started_writer: EventWriter<CollisionStarted>,
+    mut ended_writer: EventWriter<CollisionEnded>,
+) {
+    // far enough to catch the biggest collider around
+    let reach = query
+        .iter()
+        .map(|(_, _, collider)| collider.shape.extent())
+        .fold(0.0, f32::max);
+
+    let mut current = HashSet::new();
+    for (entity, trans, collider) in &query {
+        if collider.mask.is_empty() {
+            continue;
+        }
+        let position = trans.translation().xy();
+        for other in index.nearby(position, collider.shape.extent() + reach) {
+            if other == entity {
+                continue;
+            }
+            let Ok((_, other_trans, other_collider)) = query.get(other) else {
+                continue;
+            };
+            if !collider.interacts_with(other_collider) {
+                continue;
+            }
+            if collider.shape.overlaps(
+                position,
+                &other_collider.shape,
+                other_trans.translation().xy(),
+            ) {
+                current.insert((entity.min(other), entity.max(other)));
+            }
+        }
+    }
+
+    for &(a, b) in current.difference(&previous) {
+        started_writer.write(CollisionStarted { a, b });
+    }
+    for &(a, b) in previous.difference(&current) {
+        ended_writer.write(CollisionEnded { a, b });
+    }
+    *previous = current;
+}
+
+fn track_contacts(
+    mut contacts: ResMut<Contacts>,
+    mut started_reader: EventReader<CollisionStarted>,
+    mut ended_reader: EventReader<CollisionEnded>,
+) {
+    for started in started_reader.read() {
+        contacts.0.insert((started.a, started.b));
+    }
+    for ended in ended_reader.read() {
+        contacts.0.remove(&(ended.a, ended.b));
+    }
 }
@@ -0,0 +1,268 @@
+//! Chain reaction scoring.
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+
+use crate::{
+    components::*,
+    damage::DamageKind,
+    events::{BlastEvent, ChainResolved},
+    screens::Screen,
+    AppSystems, GameplaySystems, PausableSystems,
+};
+
+/// points for each enemy a chain kills
+const KILL_POINTS: u64 = 100;
+/// points for each bomb that goes off in a chain
+const BOMB_POINTS: u64 = 10;
+/// how long a chain has to be quiet before it counts as resolved
+const CHAIN_SETTLE_SECS: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Score>()
+        .init_resource::<Score>()
+        .init_resource::<ChainTracker>()
+        .add_systems(OnEnter(Screen::Gameplay), reset_score)
+        .add_systems(
+            Update,
+            track_chain_blasts
+                .in_set(AppSystems::Events)
+                .in_set(PausableSystems)
+                .in_set(GameplaySystems),
+        )
+        .add_systems(
+            Update,
+            (track_chain_kills, resolve_chains, forget_resolved_chains)
+                .chain()
+                .in_set(AppSystems::Update)
+                .in_set(PausableSystems)
+                .in_set(GameplaySystems),
+        );
+}
+
+/// The player's score for the current run.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct Score {
+    /// total points banked from resolved chains
+    pub total: u64,
+    /// number of chains resolved
+    pub chains: u32,
+    /// deepest chain so far
+    pub best_depth: u32,
+    /// most points a single chain has earned
+    pub best_chain: u64,
+}
+
+/// running totals for a chain that is still going off
+struct ChainStats {
+    depth: u32,
+    bombs: u32,
+    kills: u32,
+    points: u64,
+    settle: Timer,
+}
+
+impl ChainStats {
+    fn new() -> Self {
+        Self {
+            depth: 0,
+            bombs: 0,
+            kills: 0,
+            points: 0,
+            settle: Timer::from_seconds(CHAIN_SETTLE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// in-flight chains, keyed by their root bomb
+#[derive(Resource, Default)]
+struct ChainTracker {
+    chains: HashMap<Entity, ChainStats>,
+    /// enemies that crashed, nothing rooted at them is the player's doing
+    crashes: HashSet<Entity>,
+}
+
+/// points multiplier for anything that happens `depth` links down a chain
+pub fn chain_multiplier(depth: u32) -> u64 {
+    1 + depth as u64
+}
+
+fn reset_score(mut score: ResMut<Score>, mut tracker: ResMut<ChainTracker>) {
+    *score = Score::default();
+    tracker.chains.clear();
+    tracker.crashes.clear();
+}
+
+fn track_chain_blasts(
+    mut blast_reader: EventReader<BlastEvent>,
+    mut tracker: ResMut<ChainTracker>,
+) {
+    for blast in blast_reader.read() {
+        // enemies crashing into the player aren't the player's doing,
+        // and neither is anything the crash sets off
+        if blast.damage_kind == DamageKind::Impact {
+            tracker.crashes.insert(blast.root);
+            continue;
+        }
+        if tracker.crashes.contains(&blast.root) {
+            continue;
+        }
+        let stats = tracker
+            .chains
+            .entry(blast.root)
+            .or_insert_with(ChainStats::new);
+        stats.depth = stats.depth.max(blast.depth);
+        stats.bombs += 1;
+        stats.points += BOMB_POINTS * chain_multiplier(blast.depth);
+        stats.settle.reset();
+    }
+}
+
+fn track_chain_kills(
+    killed_query: Query<&BlastedBy, (With<Enemy>, Added<Dead>)>,
+    mut tracker: ResMut<ChainTracker>,
+) {
+    for blasted in &killed_query {
+        if let Some(stats) = tracker.chains.get_mut(&blasted.root) {
+            stats.kills += 1;
+            stats.points += KILL_POINTS * chain_multiplier(blasted.depth);
+            stats.settle.reset();
+        }
+    }
+}
+
+fn resolve_chains(
+    mut tracker: ResMut<ChainTracker>,
+    mut score: ResMut<Score>,
+    mut resolved_writer: EventWriter<ChainResolved>,
+    bomb_query: Query<(Entity, Option<&Chain>), With<Bomb>>,
+    time: Res<Time>,
+) {
+    tracker.chains.retain(|root, stats| {
+        // wait until no bomb in this chain is left to go off
+        let pending = bomb_query.iter().any(|(bomb, maybe_chain)| {
+            bomb == *root || maybe_chain.is_some_and(|chain| chain.root == *root)
+        });
+        if pending {
+            stats.settle.reset();
+            return true;
+        }
+
+        stats.settle.tick(time.delta());
+        if !stats.settle.finished() {
+            return true;
+        }
+
+        // bank it
+        score.total += stats.points;
+        score.chains += 1;
+        score.best_depth = score.best_depth.max(stats.depth);
+        score.best_chain = score.best_chain.max(stats.points);
+
+        resolved_writer.write(ChainResolved {
+            root: *root,
+            depth: stats.depth,
+            bombs: stats.bombs,
+            kills: stats.kills,
+            points: stats.points,
+        });
+
+        false
+    });
+}
+
+/// a chain that's played out can't claim anything that dies later
+fn forget_resolved_chains(
+    mut commands: Commands,
+    mut resolved_reader: EventReader<ChainResolved>,
+    blasted_query: Query<(Entity, &BlastedBy)>,
+) {
+    for resolved in resolved_reader.read() {
+        for (entity, blasted) in &blasted_query {
+            if blasted.root == resolved.root {
+                commands.entity(entity).remove::<BlastedBy>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blast(root: Entity, depth: u32, damage_kind: DamageKind) -> BlastEvent {
+        BlastEvent {
+            source: root,
+            location: Vec2::ZERO,
+            range: 0.0,
+            damage_range: 0.0,
+            damage: 0,
+            damage_kind,
+            statuses: &[],
+            knockback: 0.0,
+            root,
+            depth,
+        }
+    }
+
+    /// just enough of an app to run `track_chain_blasts` over `blasts`
+    fn track(blasts: impl FnOnce(&mut World)) -> App {
+        let mut app = App::new();
+        app.add_event::<BlastEvent>()
+            .init_resource::<ChainTracker>()
+            .add_systems(Update, track_chain_blasts);
+        blasts(app.world_mut());
+        app.update();
+        app
+    }
+
+    #[test]
+    fn multiplier_grows_by_one_per_link() {
+        assert_eq!(chain_multiplier(0), 1);
+        assert_eq!(chain_multiplier(1), 2);
+        assert_eq!(chain_multiplier(7), 8);
+    }
+
+    #[test]
+    fn bomb_blasts_add_up_under_their_root() {
+        let mut root = Entity::PLACEHOLDER;
+        let app = track(|world| {
+            root = world.spawn_empty().id();
+            world.send_event(blast(root, 0, DamageKind::Blast));
+            world.send_event(blast(root, 2, DamageKind::Fire));
+        });
+
+        let stats = &app.world().resource::<ChainTracker>().chains[&root];
+        assert_eq!(stats.bombs, 2);
+        assert_eq!(stats.depth, 2);
+        assert_eq!(
+            stats.points,
+            BOMB_POINTS * (chain_multiplier(0) + chain_multiplier(2))
+        );
+    }
+
+    #[test]
+    fn crash_impacts_are_not_scored() {
+        let app = track(|world| {
+            let crashed = world.spawn_empty().id();
+            world.send_event(blast(crashed, 0, DamageKind::Impact));
+        });
+
+        assert!(app.world().resource::<ChainTracker>().chains.is_empty());
+    }
+
+    #[test]
+    fn blasts_set_off_by_a_crash_are_not_scored() {
+        let app = track(|world| {
+            let crashed = world.spawn_empty().id();
+            world.send_event(blast(crashed, 0, DamageKind::Impact));
+            // a bomb or volatile enemy the crash set off
+            world.send_event(blast(crashed, 1, DamageKind::Blast));
+        });
+
+        assert!(app.world().resource::<ChainTracker>().chains.is_empty());
+    }
+}
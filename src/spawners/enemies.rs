@@ -2,27 +2,29 @@ use bevy::{asset::AssetIndex, prelude::*};
 use bevy_prng::WyRand;
 use bevy_rand::global::GlobalEntropy;
 use rand::prelude::*;
+use serde::Deserialize;
 
 use crate::{
     components::{
-        AssetIdx, Bomber, Done, Enemy, Flying, Ground, Health, Level, Spawner, Speed, SubType,
-        TargetDistance,
+        Archetype, Done, Enemy, Flocking, FormationMember, Health, Level, Path, Player, Spawner,
     },
     constants::{SCREEN_HALF_HEIGHT, SCREEN_HEIGHT, SCREEN_WIDTH},
-    entities::enemy::{create_enemy, EnemyAssets},
+    entities::{
+        archetype::EnemyArchetype,
+        enemy::{spawn_enemy, EnemyAssets},
+    },
     screens::Screen,
     waves::WaveState,
     AppSystems, GameplaySystems, PausableSystems,
 };
 
+/// how far from the exact corner corner spawns can land
+const CORNER_SPREAD: f32 = 48.0;
+
 pub fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (
-            tick_enemy_spawner::<Flying>,
-            tick_enemy_spawner::<Ground>,
-            tick_enemy_spawner::<Bomber>,
-        )
+        tick_enemy_spawner
             .in_set(AppSystems::TickTimers)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
@@ -30,94 +32,255 @@ pub fn plugin(app: &mut App) {
     .add_systems(OnEnter(WaveState::Done), despawn_spawner);
 }
 
-pub fn create_enemy_spawner<T>(
+/// Where on the screen a spawner sends enemies in from.
+#[derive(Reflect, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SpawnRegion {
+    /// anywhere along an edge, kept clear of the corners
+    #[default]
+    Right,
+    Left,
+    Top,
+    Bottom,
+    /// bunched up in a corner
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// a fixed height on the right edge
+    Lane(f32),
+    /// an exact spot
+    Point(f32, f32),
+}
+
+impl SpawnRegion {
+    /// a random spot in the region
+    pub fn position(&self, entropy: &mut GlobalEntropy<WyRand>) -> Vec2 {
+        let half_height: f32 = SCREEN_HEIGHT / 2.0 - 64.0;
+        let half_width: f32 = SCREEN_WIDTH / 2.0 - 64.0;
+        let jitter = Vec2::new(
+            entropy.random_range(-CORNER_SPREAD..CORNER_SPREAD),
+            entropy.random_range(-CORNER_SPREAD..CORNER_SPREAD),
+        );
+        let corner = |x: f32, y: f32| Vec2::new(x, y) + jitter;
+        match *self {
+            SpawnRegion::Right => Vec2::new(
+                SCREEN_WIDTH / 2.0,
+                entropy.random_range(-half_height..half_height),
+            ),
+            SpawnRegion::Left => Vec2::new(
+                -SCREEN_WIDTH / 2.0,
+                entropy.random_range(-half_height..half_height),
+            ),
+            SpawnRegion::Top => Vec2::new(
+                entropy.random_range(-half_width..half_width),
+                SCREEN_HEIGHT / 2.0,
+            ),
+            SpawnRegion::Bottom => Vec2::new(
+                entropy.random_range(-half_width..half_width),
+                -SCREEN_HEIGHT / 2.0,
+            ),
+            SpawnRegion::TopLeft => corner(-SCREEN_WIDTH / 2.0, SCREEN_HEIGHT / 2.0),
+            SpawnRegion::TopRight => corner(SCREEN_WIDTH / 2.0, SCREEN_HEIGHT / 2.0),
+            SpawnRegion::BottomLeft => corner(-SCREEN_WIDTH / 2.0, -SCREEN_HEIGHT / 2.0),
+            SpawnRegion::BottomRight => corner(SCREEN_WIDTH / 2.0, -SCREEN_HEIGHT / 2.0),
+            SpawnRegion::Lane(y) => Vec2::new(SCREEN_WIDTH / 2.0, y),
+            SpawnRegion::Point(x, y) => Vec2::new(x, y),
+        }
+    }
+
+    /// the way enemies head once they're on screen, unless told otherwise:
+    /// straight across from the right, otherwise towards the player
+    pub fn direction(&self, position: Vec2, player_position: Vec2) -> Vec2 {
+        match self {
+            SpawnRegion::Right | SpawnRegion::Lane(_) => Vec2::new(-1., 0.),
+            _ => (player_position - position).normalize_or(Vec2::new(-1., 0.)),
+        }
+    }
+}
+
+/// Everything about how a spawner brings its enemies in.
+#[derive(Reflect, Clone, Debug, Default)]
+pub struct SpawnPattern {
+    pub region: SpawnRegion,
+    /// initial heading in degrees, overrides the region's default direction
+    pub heading: Option<f32>,
+    /// waypoints to follow after spawning, only the leader follows them in a formation
+    pub route: Option<Path>,
+    /// spawn in groups holding a shape instead of one at a time
+    pub formation: Option<Formation>,
+    /// steer together with nearby flockmates
+    pub flocking: bool,
+}
+
+/// A group that spawns together and keeps its shape behind a leader.
+#[derive(Reflect, Deserialize, Clone, Copy, Debug)]
+pub struct Formation {
+    pub shape: FormationShape,
+    /// enemies per group, the leader included
+    pub size: usize,
+    /// distance between neighbouring slots
+    pub spacing: f32,
+}
+
+#[derive(Reflect, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FormationShape {
+    /// trailing back on both sides of the leader
+    V,
+    /// side by side with the leader in the middle
+    Line,
+    /// a ring around the leader
+    Circle,
+}
+
+impl Formation {
+    /// slot offsets for the first `count` members, the leader first,
+    /// facing +x so they can be rotated onto any heading
+    pub fn offsets(&self, count: usize) -> Vec<Vec2> {
+        (0..count)
+            .map(|i| {
+                if i == 0 {
+                    return Vec2::ZERO;
+                }
+                // alternate sides, one row further out every two members
+                let row = i.div_ceil(2) as f32 * self.spacing;
+                let side = if i % 2 == 1 { 1.0 } else { -1.0 };
+                match self.shape {
+                    FormationShape::V => Vec2::new(-row, side * row),
+                    FormationShape::Line => Vec2::new(0.0, side * row),
+                    FormationShape::Circle => {
+                        let ring = (count - 1).max(1) as f32;
+                        Vec2::from_angle(std::f32::consts::TAU * (i - 1) as f32 / ring)
+                            * self.spacing
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn create_enemy_spawner(
     commands: &mut Commands,
-    sub_type: T,
-    asset_index: AssetIdx,
+    archetype: Handle<EnemyArchetype>,
     limit: usize,
     max_at_once: usize,
     rate: f32,
-    max_speed: f32,
-    target_distance: TargetDistance,
-) where
-    T: Component + Clone,
-{
-    commands.spawn((
-        Name::new("Enemy Spawner"),
-        asset_index,
-        Enemy,
-        Spawner {
-            all_spawned: false,
-            limit,
-            max_at_once,
-            spawned: 0,
-            timer: Timer::from_seconds(rate, TimerMode::Repeating),
-        },
-        Speed(max_speed),
-        SubType::<T>(sub_type),
-        StateScoped(Screen::Gameplay),
-        target_distance,
-    ));
+    delay: f32,
+    pattern: SpawnPattern,
+) -> Entity {
+    commands
+        .spawn((
+            Name::new("Enemy Spawner"),
+            Archetype(archetype),
+            Enemy,
+            Spawner {
+                all_spawned: false,
+                delay: Timer::from_seconds(delay, TimerMode::Once),
+                limit,
+                max_at_once,
+                pattern,
+                spawned: 0,
+                timer: Timer::from_seconds(rate, TimerMode::Repeating),
+            },
+            StateScoped(Screen::Gameplay),
+        ))
+        .id()
 }
 
-fn tick_enemy_spawner<T>(
+fn tick_enemy_spawner(
     mut commands: Commands,
-    spawner_query: Query<
-        (
-            &mut Spawner,
-            &SubType<T>,
-            &AssetIdx,
-            &Speed,
-            &TargetDistance,
-            Entity,
-        ),
-        (With<Enemy>, Without<Done>),
-    >,
+    spawner_query: Query<(&mut Spawner, &Archetype, Entity), (With<Enemy>, Without<Done>)>,
     level: Single<Entity, With<Level>>,
-    enemy_query: Query<&Enemy, (With<T>, With<Health>)>,
+    player: Single<&Transform, With<Player>>,
+    enemy_query: Query<&Archetype, (With<Enemy>, With<Health>)>,
     timer: Res<Time>,
     enemy_assets: Res<EnemyAssets>,
+    archetypes: Res<Assets<EnemyArchetype>>,
     mut entropy: GlobalEntropy<WyRand>,
-) where
-    T: Component + Clone,
-{
-    for (mut spawner, sub_type, asset_index, max_speed, target_distance, spawner_ent) in
-        spawner_query
-    {
-        if enemy_query.iter().count() <= spawner.max_at_once
-            && !spawner.all_spawned
-            && spawner.spawned < spawner.limit
-        {
+) {
+    for (mut spawner, archetype, spawner_ent) in spawner_query {
+        // hold off until the group's turn comes around
+        spawner.delay.tick(timer.delta());
+        if !spawner.delay.finished() {
+            continue;
+        }
+        let handle = archetype.0.clone();
+        let Some(archetype) = archetypes.get(&handle) else {
+            continue;
+        };
+        let alive = enemy_query.iter().filter(|other| other.0 == handle).count();
+        if alive <= spawner.max_at_once && !spawner.all_spawned && spawner.spawned < spawner.limit {
             spawner.timer.tick(timer.delta());
 
             if spawner.timer.just_finished() {
-                let half_height: f32 = SCREEN_HEIGHT / 2.0 - 64.0;
-                // let speed: f32 = entropy.random_range(0.05..0.1);
-                let speed: f32 = entropy.random_range(0.05..max_speed.0);
-                let y_position: f32 = entropy.random_range(-half_height..half_height);
-                let targ_dist: f32 = target_distance.0 + entropy.random_range(-50.0..50.0);
-                let spawned = commands
-                    .spawn(create_enemy(
-                        sub_type.0.clone(),
+                let pattern = &spawner.pattern;
+                // one enemy at a time, or as much of a formation as there's room for
+                let room = spawner.max_at_once.saturating_sub(alive);
+                let offsets = pattern.formation.map_or_else(
+                    || vec![Vec2::ZERO],
+                    |formation| {
+                        formation.offsets(
+                            formation
+                                .size
+                                .min(room)
+                                .clamp(1, spawner.limit - spawner.spawned),
+                        )
+                    },
+                );
+                let (min_speed, max_speed) = archetype.speed;
+                // a formation shares one speed so it holds together
+                let speed: f32 = entropy.random_range(min_speed..=max_speed);
+                let position = pattern.region.position(&mut entropy);
+                let direction = pattern.heading.map_or_else(
+                    || pattern.region.direction(position, player.translation.xy()),
+                    |heading| Vec2::from_angle(heading.to_radians()),
+                );
+
+                let mut leader = None;
+                for offset in &offsets {
+                    let jitter = archetype.engage_jitter;
+                    let targ_dist: f32 =
+                        archetype.engage_distance + entropy.random_range(-jitter..=jitter);
+                    let spawned = spawn_enemy(
+                        &mut commands,
+                        handle.clone(),
+                        archetype,
                         &enemy_assets,
-                        // starting asset index
-                        asset_index.0,
-                        // center right side of screen
-                        Vec2::new(SCREEN_WIDTH / 2.0, y_position),
+                        // somewhere in the spawner's region, in its slot
+                        position + direction.rotate(*offset),
                         // move direction
-                        Vec2::new(-1., 0.),
+                        direction,
                         // move speed
                         speed,
                         // rough target engage distance
                         targ_dist,
-                    ))
-                    .id();
-                commands.entity(level.entity()).add_child(spawned);
+                    );
+                    match leader {
+                        Some(leader) => {
+                            commands.entity(spawned).insert(FormationMember {
+                                leader,
+                                offset: *offset,
+                                speed: SCREEN_WIDTH * speed,
+                            });
+                        }
+                        None => {
+                            leader = Some(spawned);
+                            if let Some(route) = &pattern.route {
+                                commands.entity(spawned).insert(route.clone());
+                            }
+                        }
+                    }
+                    if pattern.flocking {
+                        commands
+                            .entity(spawned)
+                            .insert(Flocking { heading: direction });
+                    }
+                    commands.entity(level.entity()).add_child(spawned);
+                }
 
                 // updoot spawner count
-                spawner.spawned += 1;
+                spawner.spawned += offsets.len();
 
-                if spawner.spawned == spawner.limit {
+                if spawner.spawned >= spawner.limit {
                     spawner.all_spawned = true;
                     commands.entity(spawner_ent).insert(Done);
                 }
@@ -135,3 +298,100 @@ fn despawn_spawner(
         info!("spawner despawned");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_rand::prelude::EntropyPlugin;
+
+    use super::*;
+
+    /// a handful of random spots in `region`
+    fn positions(region: SpawnRegion) -> Vec<Vec2> {
+        let mut app = App::new();
+        app.add_plugins(EntropyPlugin::<WyRand>::default());
+        // let the plugin set up the global source
+        app.update();
+        app.world_mut()
+            .run_system_once(move |mut entropy: GlobalEntropy<WyRand>| {
+                (0..32)
+                    .map(|_| region.position(&mut entropy))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn edges_stay_on_their_edge_and_clear_of_the_corners() {
+        let half_width = SCREEN_WIDTH / 2.0;
+        let half_height = SCREEN_HEIGHT / 2.0;
+        for position in positions(SpawnRegion::Right) {
+            assert_eq!(position.x, half_width);
+            assert!(position.y.abs() < half_height - 64.0);
+        }
+        for position in positions(SpawnRegion::Left) {
+            assert_eq!(position.x, -half_width);
+            assert!(position.y.abs() < half_height - 64.0);
+        }
+        for position in positions(SpawnRegion::Top) {
+            assert_eq!(position.y, half_height);
+            assert!(position.x.abs() < half_width - 64.0);
+        }
+        for position in positions(SpawnRegion::Bottom) {
+            assert_eq!(position.y, -half_height);
+            assert!(position.x.abs() < half_width - 64.0);
+        }
+    }
+
+    #[test]
+    fn corners_bunch_up_around_their_corner() {
+        let half_size = Vec2::new(SCREEN_WIDTH, SCREEN_HEIGHT) / 2.0;
+        for (region, corner) in [
+            (SpawnRegion::TopLeft, Vec2::new(-half_size.x, half_size.y)),
+            (SpawnRegion::TopRight, half_size),
+            (SpawnRegion::BottomLeft, -half_size),
+            (
+                SpawnRegion::BottomRight,
+                Vec2::new(half_size.x, -half_size.y),
+            ),
+        ] {
+            for position in positions(region) {
+                let offset = (position - corner).abs();
+                assert!(offset.x < CORNER_SPREAD && offset.y < CORNER_SPREAD);
+            }
+        }
+    }
+
+    #[test]
+    fn lanes_and_points_are_exact() {
+        for position in positions(SpawnRegion::Lane(40.0)) {
+            assert_eq!(position, Vec2::new(SCREEN_WIDTH / 2.0, 40.0));
+        }
+        for position in positions(SpawnRegion::Point(-10.0, 25.0)) {
+            assert_eq!(position, Vec2::new(-10.0, 25.0));
+        }
+    }
+
+    #[test]
+    fn right_edge_and_lanes_head_straight_across() {
+        let player = Vec2::new(-200.0, -150.0);
+        for region in [SpawnRegion::Right, SpawnRegion::Lane(40.0)] {
+            let position = Vec2::new(SCREEN_WIDTH / 2.0, 40.0);
+            assert_eq!(region.direction(position, player), Vec2::NEG_X);
+        }
+    }
+
+    #[test]
+    fn everything_else_heads_for_the_player() {
+        let player = Vec2::new(-200.0, 0.0);
+        let position = Vec2::new(-200.0, SCREEN_HEIGHT / 2.0);
+        let direction = SpawnRegion::Top.direction(position, player);
+        assert!(direction.abs_diff_eq(Vec2::NEG_Y, 1e-6));
+
+        // right on top of the player, fall back to heading left
+        assert_eq!(
+            SpawnRegion::Point(0.0, 0.0).direction(player, player),
+            Vec2::NEG_X
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Status effects: ticking them down, what they do each tick, and how they show.
+
+use bevy::prelude::*;
+
+use crate::{
+    components::*, damage::DamageKind, events::DamageEvent, AppSystems, GameplaySystems,
+    PausableSystems,
+};
+
+/// colour swaps per second while flashing from damage
+const FLASH_RATE: f32 = 10.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        tick_status_effects
+            .in_set(AppSystems::TickTimers)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    )
+    .add_systems(
+        Update,
+        (hold_still_while_disabled, tint_status_effects)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+fn tick_status_effects(
+    mut query: Query<(Entity, &mut StatusEffects), Without<Dead>>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut effects) in &mut query {
+        for effect in effects.0.iter_mut() {
+            effect.duration.tick(time.delta());
+
+            let Some(tick) = &mut effect.tick else {
+                continue;
+            };
+            tick.tick(time.delta());
+            if !tick.just_finished() {
+                continue;
+            }
+            match effect.kind {
+                StatusKind::Burn => {
+                    damage_writer.write(DamageEvent {
+                        target: entity,
+                        amount: effect.stacks as i32,
+                        kind: DamageKind::Fire,
+                        from_status: true,
+                    });
+                }
+                StatusKind::Stun | StatusKind::Slow | StatusKind::Freeze => {}
+            }
+        }
+
+        // wear off
+        effects.0.retain(|effect| !effect.duration.finished());
+    }
+}
+
+/// stops enemies in their tracks while stunned or frozen, then lets them carry on
+fn hold_still_while_disabled(
+    mut commands: Commands,
+    query: Query<
+        (
+            Entity,
+            &StatusEffects,
+            Has<Moving>,
+            Has<Attacking>,
+            Has<WasMoving>,
+            Has<WasAttacking>,
+        ),
+        (With<Enemy>, Without<Dead>),
+    >,
+) {
+    for (entity, effects, is_moving, is_attacking, was_moving, was_attacking) in &query {
+        if effects.is_immobilized() {
+            if is_moving {
+                commands
+                    .entity(entity)
+                    .try_remove::<Moving>()
+                    .insert_if_new(WasMoving);
+            }
+            if is_attacking {
+                commands
+                    .entity(entity)
+                    .try_remove::<Attacking>()
+                    .insert_if_new(WasAttacking);
+            }
+        } else {
+            if was_moving {
+                commands
+                    .entity(entity)
+                    .remove::<WasMoving>()
+                    // resume movement
+                    .insert(Moving);
+            }
+            if was_attacking {
+                commands
+                    .entity(entity)
+                    .remove::<WasAttacking>()
+                    // resume attacking
+                    .insert(Attacking);
+            }
+        }
+    }
+}
+
+/// the damage flash wins, otherwise the most important effect tints the sprite
+fn tint_status_effects(
+    mut query: Query<(&mut Sprite, &StatusEffects, Option<&Damaged>), (With<Enemy>, Without<Dead>)>,
+) {
+    for (mut sprite, effects, maybe_damaged) in &mut query {
+        let flashing = maybe_damaged.is_some_and(|damaged| {
+            !damaged.timer.finished()
+                && (damaged.timer.remaining_secs() * FLASH_RATE) as u32 % 2 == 0
+        });
+        sprite.color = if flashing {
+            Color::srgb(1.0, 0.0, 0.0)
+        } else {
+            effects.tint().unwrap_or(Color::WHITE)
+        };
+    }
+}
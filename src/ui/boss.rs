@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::*, screens::Screen, theme::widget, AppSystems, GameplaySystems, PausableSystems,
+};
+
+const BAR_WIDTH: f32 = 480.0;
+const BAR_HEIGHT: f32 = 16.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (create_boss_health_bar, sync_boss_health_bar)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+fn create_boss_health_bar(mut commands: Commands, boss_query: Query<Entity, Added<Boss>>) {
+    for boss in &boss_query {
+        commands.spawn((
+            Name::new("Boss Health Bar"),
+            BossHealthBar(boss),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                width: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            // Don't block picking events for other UI roots.
+            Pickable::IGNORE,
+            GlobalZIndex(2),
+            StateScoped(Screen::Gameplay),
+            children![
+                widget::label("Boss"),
+                (
+                    Node {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.05, 0.05)),
+                    children![(
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.8, 0.1, 0.1)),
+                        BossHealthFill(boss),
+                    )],
+                )
+            ],
+        ));
+    }
+}
+
+fn sync_boss_health_bar(
+    mut commands: Commands,
+    bar_query: Query<(Entity, &BossHealthBar)>,
+    mut fill_query: Query<(&mut Node, &BossHealthFill)>,
+    boss_query: Query<&Health, (With<Boss>, Without<Dead>)>,
+) {
+    for (mut node, fill) in &mut fill_query {
+        if let Ok(health) = boss_query.get(fill.0) {
+            let fraction = (health.current as f32 / health.max.max(1) as f32).clamp(0.0, 1.0);
+            node.width = Val::Percent(fraction * 100.0);
+        }
+    }
+
+    // the boss is dead, take its bar away
+    for (bar, boss) in &bar_query {
+        if boss_query.get(boss.0).is_err() {
+            commands.entity(bar).despawn();
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 
+mod boss;
 mod player;
+mod score;
 mod wave;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((player::plugin, wave::plugin));
+    app.add_plugins((boss::plugin, player::plugin, score::plugin, wave::plugin));
 }
@@ -1,14 +1,23 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::*, screens::Screen, theme::widget, AppSystems, GameplaySystems, PausableSystems,
+    components::*,
+    screens::Screen,
+    theme::{palette::LABEL_TEXT, widget},
+    AppSystems, GameplaySystems, PausableSystems,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), create_player_hp_ui);
     app.add_systems(
         Update,
-        sync_player_hp_box
+        (
+            sync_player_hp_box,
+            sync_bomb_ammo_label,
+            sync_bomb_kind_label,
+            sync_detonation_label,
+            flash_refused_label,
+        )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
             .in_set(GameplaySystems),
@@ -48,6 +57,43 @@ pub fn create_player_hp_ui(mut commands: Commands) {
                 },
                 PlayerLabel,
                 HealthLabel,
+            ),
+            (
+                widget::label("Bombs:"),
+                Node {
+                    justify_self: JustifySelf::Start,
+                    margin: UiRect::left(Val::Px(20.0)),
+                    ..default()
+                }
+            ),
+            (
+                widget::label(""),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                },
+                PlayerLabel,
+                AmmoLabel,
+            ),
+            (
+                widget::label(""),
+                Node {
+                    justify_self: JustifySelf::End,
+                    margin: UiRect::left(Val::Px(20.0)),
+                    ..default()
+                },
+                PlayerLabel,
+                BombKindLabel,
+            ),
+            (
+                widget::label(""),
+                Node {
+                    justify_self: JustifySelf::End,
+                    margin: UiRect::left(Val::Px(20.0)),
+                    ..default()
+                },
+                PlayerLabel,
+                DetonationLabel,
             )
         ],
     ));
@@ -60,3 +106,49 @@ pub fn sync_player_hp_box(
     let val = player.current;
     label.0 = format!("{val}");
 }
+
+pub fn sync_bomb_ammo_label(
+    mut label: Single<&mut Text, (With<PlayerLabel>, With<AmmoLabel>)>,
+    player: Single<&BombAmmo, With<Player>>,
+) {
+    let current = player.current;
+    let capacity = player.capacity;
+    label.0 = format!("{current}/{capacity}");
+}
+
+pub fn sync_bomb_kind_label(
+    mut label: Single<&mut Text, (With<PlayerLabel>, With<BombKindLabel>)>,
+    player: Single<&SelectedBomb, With<Player>>,
+) {
+    let name = player.0.name();
+    label.0 = format!("[{name}]");
+}
+
+pub fn sync_detonation_label(
+    mut label: Single<&mut Text, (With<PlayerLabel>, With<DetonationLabel>)>,
+    player: Single<&DetonationMode, With<Player>>,
+) {
+    let name = player.name();
+    label.0 = name.to_string();
+}
+
+fn flash_refused_label(
+    mut commands: Commands,
+    mut label_query: Query<(Entity, &mut TextColor, &mut Refused)>,
+    time: Res<Time>,
+) {
+    for (entity, mut text_color, mut refused) in &mut label_query {
+        refused.timer.tick(time.delta());
+        let remaining = (refused.timer.remaining_secs() * 10.0) as u32;
+        if remaining % 2 == 0 {
+            text_color.0 = Color::srgb(1.0, 0.0, 0.0);
+        } else {
+            text_color.0 = LABEL_TEXT;
+        }
+
+        if refused.timer.just_finished() {
+            text_color.0 = LABEL_TEXT;
+            commands.entity(entity).remove::<Refused>();
+        }
+    }
+}
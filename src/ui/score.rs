@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::*, score::Score, screens::Screen, theme::widget, AppSystems, GameplaySystems,
+    PausableSystems,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), create_score_ui);
+    app.add_systems(
+        Update,
+        sync_score_label
+            .run_if(resource_changed::<Score>)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+pub fn create_score_ui(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Score UI"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            align_items: AlignItems::Start,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(10.0),
+            ..default()
+        },
+        // Don't block picking events for other UI roots.
+        Pickable::IGNORE,
+        GlobalZIndex(2),
+        StateScoped(Screen::Gameplay),
+        children![widget::label("Score:"), (widget::label("0"), ScoreLabel),],
+    ));
+}
+
+pub fn sync_score_label(mut label: Single<&mut Text, With<ScoreLabel>>, score: Res<Score>) {
+    let val = score.total;
+    label.0 = format!("{val}");
+}
@@ -7,7 +7,10 @@ use crate::{
     components::{Countdown, Dead, Enemy, EnemyLabel, Health, Wave, WaveLabel},
     screens::Screen,
     theme::prelude::*,
-    waves::WaveState,
+    waves::{
+        script::{plan_wave, WaveAssets, WaveEvent, WaveScript},
+        WaveState,
+    },
     AppSystems, GameplaySystems, PausableSystems,
 };
 
@@ -30,18 +33,33 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Component)]
 struct WaveScreen;
 
-fn spawn_wave_ui(mut commands: Commands, wave_query: Single<&Wave>) {
+fn spawn_wave_ui(
+    mut commands: Commands,
+    wave_query: Single<&Wave>,
+    wave_assets: Res<WaveAssets>,
+    scripts: Res<Assets<WaveScript>>,
+) {
     let level = wave_query.level;
-    commands.spawn((
-        WaveScreen,
-        widget::ui_root("Wave Screen"),
-        StateScoped(Screen::Gameplay),
-        StateScoped(WaveState::Announce),
-        Countdown {
-            timer: Timer::from_seconds(3.0, TimerMode::Once),
-        },
-        children![widget::header(format!("Wave {level}"))],
-    ));
+    let plan = plan_wave(&wave_query, scripts.get(&wave_assets.script));
+    commands
+        .spawn((
+            WaveScreen,
+            widget::ui_root("Wave Screen"),
+            StateScoped(Screen::Gameplay),
+            StateScoped(WaveState::Announce),
+            Countdown {
+                timer: Timer::from_seconds(3.0, TimerMode::Once),
+            },
+            children![widget::header(format!("Wave {level}"))],
+        ))
+        .with_children(|parent| {
+            // scripted waves can say a little something
+            for event in &plan.events {
+                if let WaveEvent::Banner(text) = event {
+                    parent.spawn(widget::label(text.clone()));
+                }
+            }
+        });
 }
 
 fn wave_ui_fade(
@@ -0,0 +1,69 @@
+use crate::{
+    components::*,
+    entities::bombs::{bomb_toss, create_blast, THROW_OFFSET},
+    AppSystems, GameplaySystems, PausableSystems,
+};
+use bevy::{prelude::*, window::PrimaryWindow};
+
+/// how many points to draw the toss arc with
+const ARC_SEGMENTS: usize = 24;
+const ARC_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+const DAMAGE_RADIUS_COLOR: Color = Color::srgba(1.0, 0.3, 0.2, 0.6);
+const CHAIN_RADIUS_COLOR: Color = Color::srgba(1.0, 0.8, 0.2, 0.6);
+const CHAINED_BOMB_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+/// used for everything when out of bombs
+const EMPTY_COLOR: Color = Color::srgba(0.5, 0.5, 0.5, 0.4);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        draw_aim_preview
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+fn draw_aim_preview(
+    mut gizmos: Gizmos,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+    player_query: Single<(&Transform, &BombAmmo, &SelectedBomb), With<Player>>,
+    bomb_query: Query<&GlobalTransform, (With<Bomb>, Without<Exploding>, Without<WillExplode>)>,
+) {
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let (camera, camera_trans) = *camera_query;
+    let Ok(target) = camera.viewport_to_world_2d(camera_trans, cursor) else {
+        return;
+    };
+
+    let (player_trans, ammo, selected) = *player_query;
+    let has_ammo = ammo.current > 0;
+    let pick = |color: Color| if has_ammo { color } else { EMPTY_COLOR };
+
+    // the arc the bomb will follow
+    let toss = bomb_toss(player_trans.translation.xy() + THROW_OFFSET, target);
+    let arc = (0..=ARC_SEGMENTS).map(|step| toss.sample(step as f32 / ARC_SEGMENTS as f32));
+    gizmos.linestrip_2d(arc, pick(ARC_COLOR));
+
+    // what the blast will reach, assuming it starts a chain
+    let blast = create_blast(
+        selected.0,
+        Entity::PLACEHOLDER,
+        target,
+        Entity::PLACEHOLDER,
+        0,
+    );
+    gizmos.circle_2d(target, blast.damage_range, pick(DAMAGE_RADIUS_COLOR));
+    gizmos.circle_2d(target, blast.range, pick(CHAIN_RADIUS_COLOR));
+
+    // bombs that would get set off
+    for bomb_trans in &bomb_query {
+        let bomb_pos = bomb_trans.translation().xy();
+        if bomb_pos.distance(target) < blast.range {
+            gizmos.circle_2d(bomb_pos, 24.0, pick(CHAINED_BOMB_COLOR));
+        }
+    }
+}
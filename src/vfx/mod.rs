@@ -3,14 +3,16 @@ use bevy_asset_loader::prelude::*;
 
 use crate::assets::AssetsState;
 
+pub mod aim;
 pub mod explosion;
+pub mod shield;
 
 pub(super) fn plugin(app: &mut App) {
     app.configure_loading_state(
         LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<VfxAssets>(),
     );
 
-    app.add_plugins(explosion::plugin);
+    app.add_plugins((aim::plugin, explosion::plugin, shield::plugin));
 }
 
 #[derive(AssetCollection, Resource)]
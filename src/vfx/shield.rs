@@ -0,0 +1,31 @@
+use crate::{components::*, AppSystems, GameplaySystems, PausableSystems};
+use bevy::prelude::*;
+
+const BUBBLE_COLOR: Color = Color::srgba(0.4, 0.7, 1.0, 0.5);
+/// how much the bubble grows and shrinks while it shimmers
+const BUBBLE_PULSE: f32 = 4.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        draw_shield_bubbles
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .in_set(GameplaySystems),
+    );
+}
+
+fn draw_shield_bubbles(
+    mut gizmos: Gizmos,
+    carrier_query: Query<(&GlobalTransform, &ShieldCarrier), Without<Dead>>,
+    time: Res<Time>,
+) {
+    let pulse = time.elapsed_secs().sin() * BUBBLE_PULSE;
+    for (trans, shield) in &carrier_query {
+        gizmos.circle_2d(
+            trans.translation().xy(),
+            shield.radius + pulse,
+            BUBBLE_COLOR,
+        );
+    }
+}
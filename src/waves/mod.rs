@@ -1,5 +1,6 @@
 //! The game's wave states
 
+pub mod script;
 mod wave;
 
 use bevy::prelude::*;
@@ -17,5 +18,6 @@ pub enum WaveState {
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_state::<WaveState>().add_plugins(wave::plugin);
+    app.init_state::<WaveState>()
+        .add_plugins((script::plugin, wave::plugin));
 }
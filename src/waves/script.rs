@@ -0,0 +1,205 @@
+//! Scripted waves, loaded from a `.waves.ron` file.
+//! Once the script runs out, waves are generated procedurally.
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, LoadContext},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use bevy_asset_loader::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    assets::AssetsState,
+    components::{Path, Wave},
+    spawners::enemies::{Formation, FormationShape, SpawnRegion},
+};
+
+/// procedural waves bring a boss along every this many levels
+const BOSS_EVERY: u32 = 5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<WaveScript>()
+        .init_asset_loader::<WaveScriptLoader>()
+        .configure_loading_state(
+            LoadingStateConfig::new(AssetsState::LoadGameplay).load_collection::<WaveAssets>(),
+        );
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct WaveAssets {
+    #[asset(path = "waves/campaign.waves.ron")]
+    pub script: Handle<WaveScript>,
+}
+
+/// The hand-made waves, played in order.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct WaveScript {
+    /// approach routes spawn groups can send enemies along, by name
+    #[serde(default)]
+    pub routes: HashMap<String, RouteDef>,
+    pub waves: Vec<WaveDef>,
+}
+
+impl WaveScript {
+    /// the scripted wave for a level, if the script goes that far
+    pub fn get(&self, level: u32) -> Option<&WaveDef> {
+        let index = level.checked_sub(1)?;
+        self.waves.get(index as usize)
+    }
+}
+
+/// A winding way in for enemies to follow.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RouteDef {
+    pub points: Vec<(f32, f32)>,
+    /// curve smoothly through the points instead of cutting straight between them
+    #[serde(default)]
+    pub smooth: bool,
+}
+
+impl RouteDef {
+    pub fn to_path(&self) -> Path {
+        let points = self.points.iter().map(|(x, y)| Vec2::new(*x, *y)).collect();
+        if self.smooth {
+            Path::spline(points)
+        } else {
+            Path::polyline(points)
+        }
+    }
+}
+
+/// One wave: the groups of enemies it sends and anything special that happens.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct WaveDef {
+    pub groups: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub events: Vec<WaveEvent>,
+}
+
+/// A batch of one enemy archetype, handled by a single spawner.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpawnGroup {
+    /// name of the archetype in the enemy roster
+    pub archetype: String,
+    pub count: usize,
+    pub max_at_once: usize,
+    /// seconds between spawns
+    #[serde(default = "default_rate")]
+    pub rate: f32,
+    /// seconds after the wave starts before this group begins spawning
+    #[serde(default)]
+    pub delay: f32,
+    /// where on screen they come in from
+    #[serde(default)]
+    pub region: SpawnRegion,
+    /// initial heading in degrees, 0 is right and 90 is up
+    #[serde(default)]
+    pub heading: Option<f32>,
+    /// name of a route in the script to follow after spawning
+    #[serde(default)]
+    pub route: Option<String>,
+    /// come in as groups holding a shape
+    #[serde(default)]
+    pub formation: Option<Formation>,
+    /// steer together like a flock of birds
+    #[serde(default)]
+    pub flocking: bool,
+}
+
+fn default_rate() -> f32 {
+    1.0
+}
+
+/// Something out of the ordinary that happens when a wave starts.
+#[derive(Deserialize, Debug, Clone)]
+pub enum WaveEvent {
+    /// extra line of text on the wave announcement
+    Banner(String),
+    /// permanently raises the player's bomb capacity
+    BonusAmmo(u32),
+}
+
+/// the wave to play for the current level, from the script or made up on the spot
+pub fn plan_wave(wave: &Wave, script: Option<&WaveScript>) -> WaveDef {
+    script
+        .and_then(|script| script.get(wave.level))
+        .cloned()
+        .unwrap_or_else(|| procedural_wave(wave))
+}
+
+/// the old wave ladder: flyers always, in formation after level 2, ground troops after level 1,
+/// bombers after level 3, splitters after level 5, shield carriers after level 6, and a boss every few levels
+fn procedural_wave(wave: &Wave) -> WaveDef {
+    let group = |archetype: &str, count: usize, max_at_once: usize| SpawnGroup {
+        archetype: archetype.to_string(),
+        count,
+        max_at_once,
+        rate: default_rate(),
+        delay: 0.0,
+        region: SpawnRegion::default(),
+        heading: None,
+        route: None,
+        formation: None,
+        flocking: false,
+    };
+
+    let mut flyers = group("flying", wave.limit, wave.max_at_once);
+    if wave.level > 2 {
+        // flyers start turning up in clumps, which is what chain reactions are for
+        flyers.formation = Some(Formation {
+            shape: FormationShape::V,
+            size: if wave.level > 8 { 5 } else { 3 },
+            spacing: 40.0,
+        });
+    }
+    let mut groups = vec![flyers];
+    if wave.level > 1 {
+        groups.push(group("ground", wave.limit / 2, wave.max_at_once / 2));
+    }
+    if wave.level > 3 {
+        groups.push(group("bomber", wave.limit / 3, wave.max_at_once / 3));
+    }
+    if wave.level > 5 {
+        groups.push(group(
+            "splitter",
+            wave.limit / 4,
+            (wave.max_at_once / 3).max(1),
+        ));
+    }
+    if wave.level > 6 {
+        groups.push(group("carrier", wave.level as usize / 6, 1));
+    }
+    if wave.level % BOSS_EVERY == 0 {
+        groups.push(group("boss", 1, 1));
+    }
+
+    WaveDef {
+        groups,
+        events: Vec::new(),
+    }
+}
+
+#[derive(Default)]
+struct WaveScriptLoader;
+
+impl AssetLoader for WaveScriptLoader {
+    type Asset = WaveScript;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
@@ -1,17 +1,18 @@
 use bevy::prelude::*;
-use bevy_prng::WyRand;
-use bevy_rand::global::GlobalEntropy;
-use rand::prelude::*;
 
 use crate::{
-    components::{
-        AssetIdx, Bomber, Dead, Enemy, Flying, Ground, Health, Spawner, TargetDistance, Wave,
+    components::{BombAmmo, Dead, Enemy, Health, Player, Spawner, Wave},
+    entities::{
+        archetype::{EnemyArchetype, EnemyRoster},
+        enemy::{EnemyAssets, PendingSplits},
     },
-    constants::{SCREEN_HALF_HEIGHT, SCREEN_HALF_WIDTH, SCREEN_WIDTH},
     events::EnemyDiedEvent,
     screens::Screen,
-    spawners::enemies::create_enemy_spawner,
-    waves::WaveState,
+    spawners::enemies::{create_enemy_spawner, SpawnPattern},
+    waves::{
+        script::{plan_wave, WaveAssets, WaveEvent, WaveScript},
+        WaveState,
+    },
     AppSystems, GameplaySystems, PausableSystems,
 };
 
@@ -44,53 +45,58 @@ fn spawn_wave(
     mut commands: Commands,
     wave: Single<&Wave>,
     mut next_state: ResMut<NextState<WaveState>>,
-    mut entropy: GlobalEntropy<WyRand>,
+    mut player_ammo: Single<&mut BombAmmo, With<Player>>,
+    enemy_assets: Res<EnemyAssets>,
+    wave_assets: Res<WaveAssets>,
+    rosters: Res<Assets<EnemyRoster>>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    scripts: Res<Assets<WaveScript>>,
 ) {
-    // always create flying enemy
-    create_enemy_spawner(
-        &mut commands,
-        Flying,
-        AssetIdx(0),
-        wave.limit,
-        wave.max_at_once,
-        1.0,
-        0.3,
-        TargetDistance(
-            SCREEN_HALF_HEIGHT
-            // entropy.random_range(SCREEN_HALF_HEIGHT - 100.0..SCREEN_HALF_HEIGHT + 100.0),
-        ),
-    );
+    let Some(roster) = rosters.get(&enemy_assets.roster) else {
+        error!("enemy roster is not loaded");
+        return;
+    };
 
-    if wave.level > 1 {
+    let script = scripts.get(&wave_assets.script);
+    let plan = plan_wave(&wave, script);
+    for group in &plan.groups {
+        let Some((archetype, _)) = roster.find(&group.archetype, &archetypes) else {
+            warn!("no enemy archetype named {}", group.archetype);
+            continue;
+        };
+        let route = group.route.as_ref().and_then(|name| {
+            let route = script.and_then(|script| script.routes.get(name));
+            if route.is_none() {
+                warn!("no route named {name}");
+            }
+            route.map(|route| route.to_path())
+        });
         create_enemy_spawner(
             &mut commands,
-            Ground,
-            AssetIdx(14 * 4),
-            wave.limit / 2,
-            wave.max_at_once / 2,
-            1.0,
-            0.15,
-            TargetDistance(
-                SCREEN_HALF_HEIGHT
-                // entropy.random_range(SCREEN_HALF_HEIGHT - 100.0..SCREEN_HALF_HEIGHT + 100.0),
-            ),
+            archetype,
+            group.count,
+            group.max_at_once,
+            group.rate,
+            group.delay,
+            SpawnPattern {
+                region: group.region,
+                heading: group.heading,
+                route,
+                formation: group.formation,
+                flocking: group.flocking,
+            },
         );
     }
 
-    if wave.level > 3 {
-        create_enemy_spawner(
-            &mut commands,
-            Bomber,
-            AssetIdx(32 * 4),
-            wave.limit / 3,
-            wave.max_at_once / 3,
-            1.0,
-            0.15,
-            TargetDistance(
-                SCREEN_HALF_WIDTH + (SCREEN_HALF_WIDTH / 2.0)
-                // entropy.random_range(0.0..(SCREEN_WIDTH / 4.0))
-            ),
-        );
+    for event in &plan.events {
+        match event {
+            // shown by the announcement
+            WaveEvent::Banner(_) => {}
+            WaveEvent::BonusAmmo(amount) => {
+                player_ammo.capacity += *amount;
+                player_ammo.refill();
+            }
+        }
     }
 
     next_state.set(WaveState::Running);
@@ -99,14 +105,18 @@ fn spawn_wave(
 fn handle_enemy_died(
     spawn_query: Query<&Spawner, With<Enemy>>,
     enemy_query: Query<&Enemy, (With<Health>, Without<Dead>)>,
+    pending_splits: Res<PendingSplits>,
     mut event_reader: EventReader<EnemyDiedEvent>,
     mut next_state: ResMut<NextState<WaveState>>,
 ) {
     // an enemy died
     for _event in event_reader.read() {
-        // if no enemies are left alive
+        // if no enemies are left alive or still to come out of a splitter
         // and all enemy spawners have spawned all their entities
-        if enemy_query.is_empty() && spawn_query.iter().all(|s| s.all_spawned) {
+        if enemy_query.is_empty()
+            && pending_splits.0 == 0
+            && spawn_query.iter().all(|s| s.all_spawned)
+        {
             // then say spawning is over
             info!("wave is done");
             next_state.set(WaveState::Done);
@@ -130,13 +140,15 @@ fn observe_enemy_died(
     trigger: Trigger<EnemyDiedEvent>,
     spawn_query: Query<&Spawner, With<Enemy>>,
     enemy_query: Query<&Enemy, (With<Health>, Without<Dead>)>,
+    pending_splits: Res<PendingSplits>,
     mut next_state: ResMut<NextState<WaveState>>,
 ) {
     // an enemy died
 
-    // if no enemies are left alive
+    // if no enemies are left alive or still to come out of a splitter
     // and all enemy spawners have spawned all their entities
-    if enemy_query.is_empty() && spawn_query.iter().all(|s| s.all_spawned) {
+    if enemy_query.is_empty() && pending_splits.0 == 0 && spawn_query.iter().all(|s| s.all_spawned)
+    {
         // then say spawning is over
         info!("wave is done");
         next_state.set(WaveState::Done);